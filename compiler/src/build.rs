@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::{Executor, ExecutorResult};
+
+pub type ExitCode = Option<i32>;
+pub type Stderr = String;
+
+// A subcommand ninja reported as failed. Its rule and module come from the edges in build.ninja,
+// the module is only known when everything the failed outputs are built from is a single file
+#[derive(Debug, PartialEq)]
+pub struct ToolFailure {
+    pub outputs: Vec<String>,
+    pub rule: Option<String>,
+    pub module: Option<String>,
+    pub command: String,
+    pub output: String,
+}
+
+// Ninja failures are only parsed when its stdout is captured, otherwise it goes to the terminal.
+// The exit code is None when the build was killed by a signal
+#[derive(Debug)]
+pub enum BuildError {
+    Failed(ExitCode, Stderr, Vec<ToolFailure>),
+    Invalid(String),
+    Spawn(String, io::Error),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Invalid(msg) => write!(f, "{}", msg),
+            BuildError::Spawn(program, err) => write!(f, "Cannot run '{}': {}", program, err),
+            BuildError::Failed(Some(code), _, _) => {
+                write!(f, "Build failed with exit code {}", code)
+            }
+            BuildError::Failed(None, _, _) => write!(f, "Build was terminated by a signal"),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+pub struct NinjaExecutor<'a> {
+    ninja: &'a str,
+    capture_output: bool,
+}
+
+pub fn ninja_executor(ninja: &str, capture_output: bool) -> NinjaExecutor<'_> {
+    NinjaExecutor {
+        ninja,
+        capture_output,
+    }
+}
+
+impl Executor for NinjaExecutor<'_> {
+    fn execute(&self, build_dir: &str) -> ExecutorResult {
+        let stdout = match self.capture_output {
+            true => Stdio::piped(),
+            false => Stdio::inherit(),
+        };
+
+        let output = Command::new(self.ninja)
+            .arg("-C")
+            .arg(build_dir)
+            .stdout(stdout)
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|err| BuildError::Spawn(self.ninja.to_string(), err))?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        // A build.ninja that cannot be read leaves the failures without a rule or module
+        let build_ninja =
+            fs::read_to_string(Path::new(build_dir).join("build.ninja")).unwrap_or_default();
+        let failures = tool_failures(&String::from_utf8_lossy(&output.stdout), &build_ninja);
+
+        Err(BuildError::Failed(
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+            failures,
+        ))
+    }
+}
+
+// Ninja prints a FAILED line with the outputs of the edge, then its command and whatever the
+// command printed, up to the next status line or ninja's own messages
+fn tool_failures(stdout: &str, build_ninja: &str) -> Vec<ToolFailure> {
+    let edges = edges(build_ninja);
+    let mut failures = Vec::<ToolFailure>::new();
+    let mut lines = stdout.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(outputs) = line.strip_prefix("FAILED: ") else {
+            continue;
+        };
+
+        // Ninja 1.12 and later put the exit code of the command ahead of the outputs
+        let outputs = match outputs.strip_prefix("[code=") {
+            Some(rest) => rest.split_once("] ").map_or("", |(_, outputs)| outputs),
+            None => outputs,
+        };
+        let outputs = outputs
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let command = lines.next().unwrap_or_default().to_string();
+        let mut output = Vec::<&str>::new();
+
+        while let Some(line) = lines.next_if(|line| !ends_output(line)) {
+            output.push(line);
+        }
+
+        let rule = outputs
+            .first()
+            .and_then(|o| edges.get(o as &str))
+            .map(|(rule, _)| rule.to_string());
+
+        failures.push(ToolFailure {
+            module: module(&outputs, &edges),
+            outputs,
+            rule,
+            command,
+            output: output.join("\n"),
+        });
+    }
+
+    failures
+}
+
+fn ends_output(line: &str) -> bool {
+    let is_status = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .is_some_and(|(progress, _)| {
+            progress
+                .split('/')
+                .all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        });
+
+    is_status || line.starts_with("FAILED: ") || line.starts_with("ninja: ")
+}
+
+pub type Edges<'a> = HashMap<&'a str, (&'a str, Vec<&'a str>)>;
+
+// Maps each output in build.ninja to the rule and inputs of the edge that builds it
+pub fn edges(build_ninja: &str) -> Edges<'_> {
+    let mut edges = Edges::new();
+
+    for line in build_ninja.lines() {
+        let Some((outputs, rest)) = line
+            .strip_prefix("build ")
+            .and_then(|edge| edge.split_once(": "))
+        else {
+            continue;
+        };
+        let mut rest = rest.split_whitespace();
+        let Some(rule) = rest.next() else {
+            continue;
+        };
+        let inputs = rest.collect::<Vec<_>>();
+
+        for output in outputs.split_whitespace() {
+            edges.insert(output, (rule, inputs.clone()));
+        }
+    }
+
+    edges
+}
+
+pub fn module(outputs: &[String], edges: &Edges) -> Option<String> {
+    fn sources<'a>(file: &'a str, edges: &Edges<'a>, found: &mut Vec<&'a str>) {
+        match edges.get(file) {
+            Some((_, inputs)) => {
+                for input in inputs {
+                    sources(input, edges, found);
+                }
+            }
+            None if !found.contains(&file) => found.push(file),
+            None => (),
+        }
+    }
+
+    let mut found = Vec::new();
+
+    for output in outputs {
+        if let Some((_, inputs)) = edges.get(output as &str) {
+            for input in inputs {
+                sources(input, edges, &mut found);
+            }
+        }
+    }
+
+    match found[..] {
+        [source] => Path::new(source)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILD_NINJA: &str = "\
+rule qbe
+  command = qbe -o $out $in
+rule cc
+  command = cc -o $out -c $in
+rule link
+  command = cc -o $out $in $link_flags
+build main.s: qbe main.il
+build main.o: cc main.s
+build sayer.s: qbe sayer.il
+build sayer.o: cc sayer.s
+build a.out: link main.o sayer.o
+default a.out
+";
+
+    #[test]
+    fn tool_failure() {
+        let stdout = "\
+[1/5] qbe -o main.s main.il
+[2/5] qbe -o sayer.s sayer.il
+FAILED: sayer.s
+qbe -o sayer.s sayer.il
+qbe:sayer.il:3: invalid type for first operand
+in instruction
+ninja: build stopped: subcommand failed.
+";
+
+        assert_eq!(
+            tool_failures(stdout, BUILD_NINJA),
+            [ToolFailure {
+                outputs: vec!["sayer.s".to_string()],
+                rule: Some("qbe".to_string()),
+                module: Some("sayer".to_string()),
+                command: "qbe -o sayer.s sayer.il".to_string(),
+                output: "qbe:sayer.il:3: invalid type for first operand\nin instruction"
+                    .to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn tool_failures_with_exit_codes() {
+        let stdout = "\
+FAILED: [code=1] main.o
+cc -o main.o -c main.s
+main.s: Assembler messages:
+[3/5] cc -o sayer.o -c sayer.s
+FAILED: [code=1] a.out
+cc -o a.out main.o sayer.o
+undefined reference to `say'
+ninja: build stopped: subcommand failed.
+";
+
+        let failures = tool_failures(stdout, BUILD_NINJA)
+            .into_iter()
+            .map(|f| (f.outputs, f.rule, f.module, f.output))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            failures,
+            [
+                (
+                    vec!["main.o".to_string()],
+                    Some("cc".to_string()),
+                    Some("main".to_string()),
+                    "main.s: Assembler messages:".to_string()
+                ),
+                (
+                    vec!["a.out".to_string()],
+                    Some("link".to_string()),
+                    None,
+                    "undefined reference to `say'".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn tool_failure_unknown_edge() {
+        let failures = tool_failures("FAILED: x.o\ncc -c x.c\n", "");
+
+        assert_eq!(failures[0].rule, None);
+        assert_eq!(failures[0].module, None);
+        assert_eq!(failures[0].output, "");
+    }
+
+    #[test]
+    fn build_failed() {
+        let build_dir = std::env::temp_dir().display().to_string();
+
+        match ninja_executor("false", true).execute(&build_dir) {
+            Err(BuildError::Failed(Some(1), stderr, failures)) => {
+                assert!(stderr.is_empty());
+                assert!(failures.is_empty());
+            }
+            result => panic!("Expected a failed build, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn build_spawn_failed() {
+        let result = ninja_executor("./no-such-ninja", true).execute(".");
+
+        assert!(matches!(result, Err(BuildError::Spawn(_, _))));
+    }
+}
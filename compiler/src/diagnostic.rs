@@ -0,0 +1,160 @@
+use std::error::Error;
+use std::fmt;
+
+use midlang::Loc;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+// Codes are stable once published so tooling can match on them, new diagnostics get new codes.
+// E0xxx are raised by frontends and the driver, E1xxx by decls, E2xxx by stmts and E3xxx by
+// exprs. Warnings follow the same scheme with a W prefix.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub code: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub loc: Option<Box<Loc>>,
+    pub path: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+pub type Diagnostics = Vec<Diagnostic>;
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(code, Severity::Error, message.into())
+    }
+
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(code, Severity::Warning, message.into())
+    }
+
+    fn new(code: &'static str, severity: Severity, message: String) -> Self {
+        Diagnostic {
+            code,
+            severity,
+            message,
+            loc: None,
+            path: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    // Diagnostics are raised at the innermost point and gain path segments on their way out, so
+    // each enclosing scope prepends its own
+    pub fn within(mut self, segment: impl Into<String>) -> Self {
+        self.path.insert(0, segment.into());
+        self
+    }
+
+    // The innermost known loc is the most precise so enclosing ones never replace it
+    pub fn at(mut self, loc: Option<&Loc>) -> Self {
+        if self.loc.is_none() {
+            self.loc = loc.cloned().map(Box::new);
+        }
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+
+        match (&self.loc, self.path.is_empty()) {
+            (Some(loc), true) => write!(f, "\n  --> {}", loc)?,
+            (Some(loc), false) => write!(f, "\n  --> {} in {}", loc, self.path.join(" > "))?,
+            (None, false) => write!(f, "\n  --> {}", self.path.join(" > "))?,
+            (None, true) => (),
+        }
+
+        for note in &self.notes {
+            write!(f, "\n  = note: {}", note)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for Diagnostic {}
+
+impl From<Box<dyn Error>> for Diagnostic {
+    fn from(err: Box<dyn Error>) -> Self {
+        match err.downcast::<Diagnostic>() {
+            Ok(diagnostic) => *diagnostic,
+            Err(err) => Diagnostic::error("E0001", err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let diagnostic = Diagnostic::error("E2005", "Assign to immutable var 'x'")
+            .with_note("declare 'x' with VarDeclMut to allow assignment")
+            .within("stmt 1")
+            .within("main")
+            .within("module");
+
+        assert_eq!(
+            diagnostic.to_string(),
+            "error[E2005]: Assign to immutable var 'x'\n  \
+             --> module > main > stmt 1\n  \
+             = note: declare 'x' with VarDeclMut to allow assignment"
+        );
+    }
+
+    #[test]
+    fn display_loc() {
+        let loc = Loc::Pos("order.bpmn".to_string(), 12, Some(5));
+        let diagnostic = Diagnostic::error("E3035", "VarRef 'x' does not have a declaration")
+            .at(Some(&loc))
+            .at(Some(&Loc::Element("Task_1".to_string())))
+            .within("main");
+
+        assert_eq!(
+            diagnostic.to_string(),
+            "error[E3035]: VarRef 'x' does not have a declaration\n  \
+             --> order.bpmn:12:5 in main"
+        );
+    }
+
+    #[test]
+    fn from_boxed_error() {
+        let boxed: Box<dyn Error> = Box::new(Diagnostic::warning("W2001", "unreachable"));
+        assert_eq!(
+            Diagnostic::from(boxed),
+            Diagnostic::warning("W2001", "unreachable")
+        );
+
+        let boxed: Box<dyn Error> = "No such file".into();
+        assert_eq!(
+            Diagnostic::from(boxed),
+            Diagnostic::error("E0001", "No such file")
+        );
+    }
+}
@@ -2,13 +2,20 @@ use std::error::Error;
 use std::fs;
 use std::io;
 use std::path::Path;
-use std::process::Command;
+use std::str::FromStr;
 
 use ninja_writer::Ninja;
 
+mod build;
+mod diagnostic;
+mod native_build;
 mod type_check;
 
-use crate::type_check::type_check;
+pub use crate::build::{ninja_executor, BuildError, NinjaExecutor, ToolFailure};
+pub use crate::diagnostic::{Diagnostic, Diagnostics, Severity};
+pub use crate::native_build::{native_executor, NativeExecutor, UpToDate};
+use crate::type_check::{check_entry_point, type_check};
+pub use crate::type_check::{Level, Rules};
 use midlang::Module;
 
 pub type FrontendLowerResult = Result<Vec<Module>, Box<dyn Error>>;
@@ -22,68 +29,170 @@ pub trait Frontend {
 pub type BuildArtifacts = Vec<(String, String)>;
 pub type BackendResult = Result<BuildArtifacts, Box<dyn Error>>;
 
+// What the object files of a build end up in, only executables need an entry point
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BuildKind {
+    Executable,
+    StaticLib,
+    SharedLib,
+    Object,
+}
+
+impl FromStr for BuildKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exe" => Ok(BuildKind::Executable),
+            "static" => Ok(BuildKind::StaticLib),
+            "shared" => Ok(BuildKind::SharedLib),
+            "object" => Ok(BuildKind::Object),
+            _ => Err(format!(
+                "Unknown build kind '{}', expected exe, static, shared or object",
+                s
+            )),
+        }
+    }
+}
+
 pub trait Backend {
     fn generate_build_artifacts(
         &self,
         modules: &[Module],
+        build_kind: BuildKind,
         ninja_writer: &mut Ninja,
     ) -> BackendResult;
 }
 
+pub type ExecutorResult = Result<(), BuildError>;
+
+// Runs the build.ninja a backend generated in the build dir
+pub trait Executor {
+    fn execute(&self, build_dir: &str) -> ExecutorResult;
+}
+
 pub struct Compiler<'a> {
     frontend: &'a dyn Frontend,
     backend: &'a dyn Backend,
+    executor: &'a dyn Executor,
     build_dir: &'a str,
-    ninja: &'a str,
+    build_kind: BuildKind,
+    rules: Rules,
 }
 
 pub fn new<'a>(
     frontend: &'a dyn Frontend,
     backend: &'a dyn Backend,
+    executor: &'a dyn Executor,
     build_dir: &'a str,
-    ninja: &'a str,
+    build_kind: BuildKind,
+    rules: Rules,
 ) -> Compiler<'a> {
     Compiler {
         frontend,
         backend,
+        executor,
         build_dir,
-        ninja,
+        build_kind,
+        rules,
     }
 }
 
+pub type CompileResult = Result<Diagnostics, Diagnostics>;
+
 impl Compiler<'_> {
-    pub fn compile(&self) -> Result<(), Box<dyn Error>> {
-        let modules = self.frontend.lower()?;
-        type_check(&modules)?;
+    pub fn compile(&self) -> CompileResult {
+        let modules = self.frontend.lower().map_err(|err| vec![err.into()])?;
+        let mut warnings = type_check(&modules, self.rules)?;
+
+        if self.build_kind == BuildKind::Executable {
+            if let Err(err) = check_entry_point(&modules) {
+                warnings.push(err);
+                return Err(warnings);
+            }
+        }
 
         let mut ninja_writer = Ninja::new();
         let mut build_artifacts = self
             .backend
-            .generate_build_artifacts(&modules, &mut ninja_writer)?;
+            .generate_build_artifacts(&modules, self.build_kind, &mut ninja_writer)
+            .map_err(|err| vec![err.into()])?;
         build_artifacts.push(("build.ninja".to_string(), ninja_writer.to_string()));
 
-        write_build_artifacts(&build_artifacts, self.build_dir)?;
-        execute_build(self.ninja, self.build_dir)?;
+        write_build_artifacts(&build_artifacts, self.build_dir).map_err(|err| {
+            vec![Diagnostic::error(
+                "E0002",
+                format!(
+                    "Cannot write build artifacts to '{}': {}",
+                    self.build_dir, err
+                ),
+            )]
+        })?;
+        self.executor
+            .execute(self.build_dir)
+            .map_err(build_diagnostics)?;
+
+        Ok(warnings)
+    }
+}
 
-        Ok(())
+// Each failed tool gets its own diagnostic under the module it was building, if known, so failures
+// can be traced back to their source
+fn build_diagnostics(err: BuildError) -> Diagnostics {
+    let (stderr, failures) = match &err {
+        BuildError::Failed(_, stderr, failures) => (stderr, failures),
+        BuildError::Invalid(_) => return vec![Diagnostic::error("E0006", err.to_string())],
+        BuildError::Spawn(_, _) => return vec![Diagnostic::error("E0003", err.to_string())],
+    };
+
+    let stderr = stderr.trim();
+    let mut diagnostics = failures
+        .iter()
+        .map(|failure| {
+            let rule = failure.rule.as_deref().unwrap_or("unknown");
+            let mut diagnostic = Diagnostic::error(
+                "E0004",
+                format!(
+                    "Rule '{}' failed to build '{}'",
+                    rule,
+                    failure.outputs.join(" ")
+                ),
+            )
+            .with_note(format!("command: {}", failure.command));
+
+            if !failure.output.is_empty() {
+                diagnostic = diagnostic.with_note(failure.output.trim_end());
+            }
+
+            match &failure.module {
+                Some(module) => diagnostic.within(module),
+                None => diagnostic,
+            }
+        })
+        .collect::<Diagnostics>();
+
+    let mut summary = Diagnostic::error("E0005", err.to_string());
+
+    if !stderr.is_empty() {
+        summary = summary.with_note(stderr);
     }
+
+    diagnostics.push(summary);
+    diagnostics
 }
 
 fn write_build_artifacts(build_artifacts: &BuildArtifacts, build_dir: &str) -> io::Result<()> {
     let build_dir = Path::new(build_dir);
     fs::create_dir_all(build_dir)?;
 
+    // Unchanged artifacts keep their timestamps so executors can tell what is up to date
     for (name, contents) in build_artifacts {
         let artifact = &build_dir.join(name);
-        fs::write(artifact, contents)?;
-    }
 
-    Ok(())
-}
-
-fn execute_build(ninja: &str, build_dir: &str) -> io::Result<()> {
-    let mut child = Command::new(ninja).arg("-C").arg(build_dir).spawn()?;
-    child.wait()?;
+        if fs::read_to_string(artifact).ok().as_deref() != Some(contents) {
+            fs::write(artifact, contents)?;
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,699 @@
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::SystemTime;
+
+use crate::build::{self, BuildError, ToolFailure};
+use crate::{Executor, ExecutorResult};
+
+// Hashes of the commands and inputs each edge was last built from, kept in the build dir
+const HASHES_FILE: &str = ".midlang_hashes";
+
+// Timestamps rebuild an edge when an input is newer than an output like ninja does. Content
+// hashes also notice changed commands and skip edges whose inputs were rewritten unchanged
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UpToDate {
+    Timestamps,
+    ContentHashes,
+}
+
+impl FromStr for UpToDate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "timestamps" => Ok(UpToDate::Timestamps),
+            "hashes" => Ok(UpToDate::ContentHashes),
+            _ => Err(format!(
+                "Unknown up to date check '{}', expected timestamps or hashes",
+                s
+            )),
+        }
+    }
+}
+
+// Runs the edges of build.ninja itself, at most jobs at a time, so no ninja binary is needed.
+// Only the subset of the ninja syntax backends generate is supported. Failed edges are always
+// reported, what their commands print is only kept in the failures when output is captured
+pub struct NativeExecutor {
+    jobs: usize,
+    up_to_date: UpToDate,
+    capture_output: bool,
+}
+
+pub fn native_executor(jobs: usize, up_to_date: UpToDate, capture_output: bool) -> NativeExecutor {
+    NativeExecutor {
+        jobs: jobs.max(1),
+        up_to_date,
+        capture_output,
+    }
+}
+
+struct Edge {
+    outputs: Vec<String>,
+    rule: String,
+    inputs: Vec<String>,
+    command: String,
+}
+
+type Defaults = Vec<String>;
+type Hashes = HashMap<String, u64>;
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Unwanted,
+    Waiting,
+    Running,
+    Done,
+}
+
+impl Executor for NativeExecutor {
+    fn execute(&self, build_dir: &str) -> ExecutorResult {
+        let dir = Path::new(build_dir);
+        let build_ninja = fs::read_to_string(dir.join("build.ninja"))
+            .map_err(|err| BuildError::Invalid(format!("Cannot read build.ninja: {}", err)))?;
+        let (edges, defaults) = parse(&build_ninja).map_err(BuildError::Invalid)?;
+
+        let producers = edges
+            .iter()
+            .enumerate()
+            .flat_map(|(i, edge)| edge.outputs.iter().map(move |o| (o as &str, i)))
+            .collect::<HashMap<_, _>>();
+        let mut states = wanted(&edges, &defaults, &producers, dir)?;
+        let build_edges = build::edges(&build_ninja);
+
+        let mut hashes = match self.up_to_date {
+            UpToDate::Timestamps => Hashes::new(),
+            UpToDate::ContentHashes => read_hashes(dir),
+        };
+        let mut built_hashes = Hashes::new();
+
+        let mut failures = Vec::<ToolFailure>::new();
+        let mut exit_code = None;
+        let mut spawn_err = None;
+
+        thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel::<(usize, io::Result<Output>)>();
+            let mut running = 0;
+
+            loop {
+                // Edges found up to date finish on the spot and can make others ready, so keep
+                // going until nothing changes. Nothing new starts once something failed
+                let mut progressed = true;
+
+                while progressed && failures.is_empty() && spawn_err.is_none() {
+                    progressed = false;
+
+                    for (i, edge) in edges.iter().enumerate() {
+                        if states[i] != State::Waiting || running == self.jobs {
+                            continue;
+                        }
+
+                        let ready = edge.inputs.iter().all(|input| {
+                            producers
+                                .get(input as &str)
+                                .is_none_or(|&p| states[p] == State::Done)
+                        });
+
+                        if !ready {
+                            continue;
+                        }
+
+                        progressed = true;
+
+                        let hash = match self.up_to_date {
+                            UpToDate::Timestamps => None,
+                            UpToDate::ContentHashes => Some(content_hash(edge, dir)),
+                        };
+
+                        let up_to_date = match hash {
+                            None => timestamps_up_to_date(edge, dir),
+                            Some(hash) => {
+                                outputs_exist(edge, dir)
+                                    && hashes.get(&edge.outputs[0]) == Some(&hash)
+                            }
+                        };
+
+                        if up_to_date {
+                            states[i] = State::Done;
+                            continue;
+                        }
+
+                        if let Some(hash) = hash {
+                            built_hashes.insert(edge.outputs[0].to_string(), hash);
+                        }
+
+                        states[i] = State::Running;
+                        running += 1;
+
+                        let tx = tx.clone();
+                        let capture_output = self.capture_output;
+
+                        scope.spawn(move || {
+                            let mut command = Command::new("sh");
+                            command.arg("-c").arg(&edge.command).current_dir(dir);
+
+                            if !capture_output {
+                                command.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+                            }
+
+                            let output = command.output();
+
+                            // The receiver outlives every job so the send cannot fail
+                            tx.send((i, output)).ok();
+                        });
+                    }
+                }
+
+                if running == 0 {
+                    break;
+                }
+
+                let Ok((i, output)) = rx.recv() else {
+                    break;
+                };
+                let edge = &edges[i];
+                running -= 1;
+
+                match output {
+                    Ok(output) if output.status.success() => {
+                        states[i] = State::Done;
+
+                        if let Some(hash) = built_hashes.remove(&edge.outputs[0]) {
+                            hashes.insert(edge.outputs[0].to_string(), hash);
+                        }
+                    }
+                    Ok(output) => {
+                        exit_code = exit_code.or(Some(output.status.code()));
+
+                        let mut tool_output = String::from_utf8_lossy(&output.stdout).into_owned();
+                        tool_output.push_str(&String::from_utf8_lossy(&output.stderr));
+
+                        failures.push(ToolFailure {
+                            outputs: edge.outputs.clone(),
+                            rule: Some(edge.rule.to_string()),
+                            module: build::module(&edge.outputs, &build_edges),
+                            command: edge.command.to_string(),
+                            output: tool_output.trim_end().to_string(),
+                        });
+                    }
+                    Err(err) => spawn_err = Some(err),
+                }
+            }
+        });
+
+        if self.up_to_date == UpToDate::ContentHashes {
+            write_hashes(dir, &hashes).map_err(|err| {
+                BuildError::Invalid(format!("Cannot write {}: {}", HASHES_FILE, err))
+            })?;
+        }
+
+        if let Some(err) = spawn_err {
+            return Err(BuildError::Spawn("sh".to_string(), err));
+        }
+
+        if !failures.is_empty() {
+            return Err(BuildError::Failed(
+                exit_code.flatten(),
+                String::new(),
+                failures,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// Marks the edges the defaults depend on, or every edge when there are no defaults. Inputs that no
+// edge builds must already exist and the graph cannot have cycles
+fn wanted(
+    edges: &[Edge],
+    defaults: &Defaults,
+    producers: &HashMap<&str, usize>,
+    dir: &Path,
+) -> Result<Vec<State>, BuildError> {
+    fn visit(
+        file: &str,
+        needed_by: Option<&str>,
+        edges: &[Edge],
+        producers: &HashMap<&str, usize>,
+        dir: &Path,
+        states: &mut Vec<State>,
+        visiting: &mut Vec<usize>,
+    ) -> Result<(), BuildError> {
+        let Some(&i) = producers.get(file) else {
+            return match (dir.join(file).exists(), needed_by) {
+                (true, _) => Ok(()),
+                (false, Some(needed_by)) => Err(BuildError::Invalid(format!(
+                    "'{}', needed by '{}', is missing and no edge builds it",
+                    file, needed_by
+                ))),
+                (false, None) => Err(BuildError::Invalid(format!(
+                    "Unknown default target '{}'",
+                    file
+                ))),
+            };
+        };
+
+        if visiting.contains(&i) {
+            return Err(BuildError::Invalid(format!(
+                "Dependency cycle through '{}'",
+                file
+            )));
+        }
+
+        if states[i] == State::Waiting {
+            return Ok(());
+        }
+
+        visiting.push(i);
+
+        for input in &edges[i].inputs {
+            visit(input, Some(file), edges, producers, dir, states, visiting)?;
+        }
+
+        visiting.pop();
+        states[i] = State::Waiting;
+
+        Ok(())
+    }
+
+    let mut states = vec![State::Unwanted; edges.len()];
+    let mut visiting = Vec::new();
+    let targets = match defaults.is_empty() {
+        true => edges.iter().flat_map(|e| e.outputs.clone()).collect(),
+        false => defaults.clone(),
+    };
+
+    for target in &targets {
+        visit(
+            target,
+            None,
+            edges,
+            producers,
+            dir,
+            &mut states,
+            &mut visiting,
+        )?;
+    }
+
+    Ok(states)
+}
+
+fn outputs_exist(edge: &Edge, dir: &Path) -> bool {
+    edge.outputs.iter().all(|o| dir.join(o).exists())
+}
+
+fn timestamps_up_to_date(edge: &Edge, dir: &Path) -> bool {
+    fn modified(file: &str, dir: &Path) -> Option<SystemTime> {
+        fs::metadata(dir.join(file)).and_then(|m| m.modified()).ok()
+    }
+
+    let oldest_output = edge.outputs.iter().map(|o| modified(o, dir)).min();
+    let newest_input = edge.inputs.iter().map(|i| modified(i, dir)).max();
+
+    match (oldest_output, newest_input) {
+        (Some(Some(output)), Some(Some(input))) => input <= output,
+        (Some(Some(_)), None) => true,
+        _ => false,
+    }
+}
+
+// The std hasher may change between Rust releases which only costs a rebuild
+fn content_hash(edge: &Edge, dir: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    edge.command.hash(&mut hasher);
+
+    for input in &edge.inputs {
+        fs::read(dir.join(input)).ok().hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+fn read_hashes(dir: &Path) -> Hashes {
+    let hashes = fs::read_to_string(dir.join(HASHES_FILE)).unwrap_or_default();
+
+    hashes
+        .lines()
+        .filter_map(|line| {
+            let (hash, output) = line.split_once(' ')?;
+            let hash = u64::from_str_radix(hash, 16).ok()?;
+
+            Some((output.to_string(), hash))
+        })
+        .collect()
+}
+
+fn write_hashes(dir: &Path, hashes: &Hashes) -> io::Result<()> {
+    let mut lines = hashes
+        .iter()
+        .map(|(output, hash)| format!("{:016x} {}\n", hash, output))
+        .collect::<Vec<_>>();
+    lines.sort();
+
+    fs::write(dir.join(HASHES_FILE), lines.concat())
+}
+
+fn parse(build_ninja: &str) -> Result<(Vec<Edge>, Defaults), String> {
+    let mut vars = HashMap::<String, String>::new();
+    let mut rules = HashMap::<&str, HashMap<&str, &str>>::new();
+    let mut builds = Vec::<(Vec<String>, &str, Vec<String>, HashMap<&str, &str>)>::new();
+    let mut defaults = Defaults::new();
+    let mut lines = build_ninja.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut scope = HashMap::new();
+
+        while let Some(nested) = lines.next_if(|l| l.starts_with(' ')) {
+            let (name, value) = var(nested)?;
+            scope.insert(name, value);
+        }
+
+        if let Some(name) = line.strip_prefix("rule ") {
+            rules.insert(name.trim(), scope);
+        } else if let Some(edge) = line.strip_prefix("build ") {
+            let (outputs, rest) = edge
+                .split_once(": ")
+                .ok_or_else(|| format!("Invalid build line '{}'", line))?;
+            let mut rest = rest.split_whitespace();
+            let rule = rest
+                .next()
+                .ok_or_else(|| format!("Build line '{}' does not name a rule", line))?;
+
+            builds.push((
+                outputs.split_whitespace().map(str::to_string).collect(),
+                rule,
+                rest.map(str::to_string).collect(),
+                scope,
+            ));
+        } else if let Some(targets) = line.strip_prefix("default ") {
+            defaults.extend(targets.split_whitespace().map(str::to_string));
+        } else {
+            let (name, value) = var(line)?;
+            let value = expand(value, &|name| vars.get(name).cloned());
+            vars.insert(name.to_string(), value);
+        }
+    }
+
+    let edges = builds
+        .into_iter()
+        .map(|(outputs, rule, inputs, scope)| {
+            let command = rules
+                .get(rule)
+                .ok_or_else(|| format!("Unknown rule '{}'", rule))?
+                .get("command")
+                .ok_or_else(|| format!("Rule '{}' does not have a command", rule))?;
+
+            let (ins, outs) = (inputs.join(" "), outputs.join(" "));
+            let lookup = |name: &str| match name {
+                "in" => Some(ins.to_string()),
+                "out" => Some(outs.to_string()),
+                _ => scope
+                    .get(name)
+                    .map(|v| v.to_string())
+                    .or_else(|| vars.get(name).cloned()),
+            };
+
+            Ok(Edge {
+                command: expand(command, &lookup),
+                outputs,
+                rule: rule.to_string(),
+                inputs,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok((edges, defaults))
+}
+
+fn var(line: &str) -> Result<(&str, &str), String> {
+    line.split_once('=')
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .ok_or_else(|| format!("Unsupported build.ninja line '{}'", line))
+}
+
+// Expands $name, ${name} and the $$, $ and $: escapes, unknown vars expand to nothing
+fn expand(value: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(i) = rest.find('$') {
+        expanded.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+
+        match rest.chars().next() {
+            Some(c @ ('$' | ' ' | ':')) => {
+                expanded.push(c);
+                rest = &rest[1..];
+            }
+            Some('{') => {
+                let end = rest.find('}').unwrap_or(rest.len());
+                expanded.push_str(&lookup(&rest[1..end]).unwrap_or_default());
+                rest = rest.get(end + 1..).unwrap_or_default();
+            }
+            _ => {
+                let end = rest.find(|c| !is_name(c)).unwrap_or(rest.len());
+                expanded.push_str(&lookup(&rest[..end]).unwrap_or_default());
+                rest = &rest[end..];
+            }
+        }
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    const BUILD_NINJA: &str = "\
+log = >> log
+
+rule cat
+  command = cat $in > $out && echo $out $log
+
+build b.txt: cat a.txt
+build c.txt: cat b.txt a.txt
+build d.txt: cat a.txt
+build all.txt: cat c.txt d.txt
+
+default all.txt
+";
+
+    fn build_dir(name: &str, build_ninja: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("midlang_{}_{}", name, std::process::id()));
+
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("build.ninja"), build_ninja).unwrap();
+        fs::write(dir.join("a.txt"), "a\n").unwrap();
+
+        dir
+    }
+
+    fn execute(dir: &Path, up_to_date: UpToDate) -> Result<Vec<String>, BuildError> {
+        fs::write(dir.join("log"), "").unwrap();
+        native_executor(4, up_to_date, true).execute(&dir.display().to_string())?;
+
+        let mut built = fs::read_to_string(dir.join("log"))
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        built.sort();
+
+        Ok(built)
+    }
+
+    // Moves a file into the past so the files built from it are newer no matter how coarse the
+    // file system timestamps are
+    fn age(dir: &Path, file: &str) {
+        let past = SystemTime::now() - Duration::from_secs(60);
+        let file = fs::File::options()
+            .write(true)
+            .open(dir.join(file))
+            .unwrap();
+
+        file.set_modified(past).unwrap();
+    }
+
+    #[test]
+    fn parse_build_ninja() -> Result<(), String> {
+        let (edges, defaults) = parse(
+            "flags = -lm\n\
+             rule link\n  command = cc -o $out $in ${flags} $$HOME\n\
+             build a$:b.out: link x.o y.o\n  flags = -lc $flags\n",
+        )?;
+
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].rule, "link");
+        assert_eq!(edges[0].command, "cc -o a$:b.out x.o y.o -lc $flags $HOME");
+        assert!(defaults.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_unknown_rule() {
+        let Err(err) = parse("build a.o: cc a.c\n") else {
+            panic!("Expected an unknown rule");
+        };
+
+        assert_eq!(err, "Unknown rule 'cc'");
+    }
+
+    #[test]
+    fn build() -> Result<(), BuildError> {
+        let dir = build_dir("build", BUILD_NINJA);
+
+        assert_eq!(
+            execute(&dir, UpToDate::Timestamps)?,
+            ["all.txt", "b.txt", "c.txt", "d.txt"]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("all.txt")).unwrap(),
+            "a\na\na\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_up_to_date_timestamps() -> Result<(), BuildError> {
+        let dir = build_dir("timestamps", BUILD_NINJA);
+
+        execute(&dir, UpToDate::Timestamps)?;
+        assert!(execute(&dir, UpToDate::Timestamps)?.is_empty());
+
+        for file in ["a.txt", "b.txt", "c.txt", "d.txt", "all.txt"] {
+            age(&dir, file);
+        }
+
+        fs::write(dir.join("b.txt"), "a\n").unwrap();
+        assert_eq!(execute(&dir, UpToDate::Timestamps)?, ["all.txt", "c.txt"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_up_to_date_content_hashes() -> Result<(), BuildError> {
+        let dir = build_dir("hashes", BUILD_NINJA);
+
+        execute(&dir, UpToDate::ContentHashes)?;
+        assert!(execute(&dir, UpToDate::ContentHashes)?.is_empty());
+
+        fs::write(dir.join("a.txt"), "a\n").unwrap();
+        assert!(execute(&dir, UpToDate::ContentHashes)?.is_empty());
+
+        fs::write(dir.join("a.txt"), "b\n").unwrap();
+        assert_eq!(
+            execute(&dir, UpToDate::ContentHashes)?,
+            ["all.txt", "b.txt", "c.txt", "d.txt"]
+        );
+
+        // Rebuilding d.txt gives the same contents so all.txt is left alone
+        fs::remove_file(dir.join("d.txt")).unwrap();
+        assert_eq!(execute(&dir, UpToDate::ContentHashes)?, ["d.txt"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_failed() {
+        let dir = build_dir(
+            "failed",
+            "rule fail\n  command = echo $out failed >&2 && exit 3\n\
+             rule cat\n  command = cat $in > $out\n\
+             build a.s: fail a.txt\n\
+             build a.o: cat a.s\n",
+        );
+
+        match execute(&dir, UpToDate::Timestamps) {
+            Err(BuildError::Failed(Some(3), stderr, failures)) => {
+                assert!(stderr.is_empty());
+                assert_eq!(
+                    failures,
+                    [ToolFailure {
+                        outputs: vec!["a.s".to_string()],
+                        rule: Some("fail".to_string()),
+                        module: Some("a".to_string()),
+                        command: "echo a.s failed >&2 && exit 3".to_string(),
+                        output: "a.s failed".to_string(),
+                    }]
+                );
+            }
+            result => panic!("Expected a failed build, got {:?}", result),
+        }
+
+        assert!(!dir.join("a.o").exists());
+    }
+
+    #[test]
+    fn build_failed_uncaptured() {
+        let dir = build_dir(
+            "failed_uncaptured",
+            "rule fail\n  command = exit 3\nbuild a.s: fail a.txt\n",
+        );
+
+        match native_executor(1, UpToDate::Timestamps, false).execute(&dir.display().to_string()) {
+            Err(BuildError::Failed(Some(3), _, failures)) => {
+                assert_eq!(failures.len(), 1);
+                assert_eq!(failures[0].rule.as_deref(), Some("fail"));
+                assert_eq!(failures[0].command, "exit 3");
+                assert!(failures[0].output.is_empty());
+            }
+            result => panic!("Expected a failed build, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn build_missing_input() {
+        let dir = build_dir(
+            "missing",
+            "rule cat\n  command = cat $in > $out\nbuild b.txt: cat z.txt\n",
+        );
+
+        match execute(&dir, UpToDate::Timestamps) {
+            Err(BuildError::Invalid(msg)) => assert_eq!(
+                msg,
+                "'z.txt', needed by 'b.txt', is missing and no edge builds it"
+            ),
+            result => panic!("Expected an invalid build, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn build_cycle() {
+        let dir = build_dir(
+            "cycle",
+            "rule cat\n  command = cat $in > $out\nbuild b.txt: cat c.txt\nbuild c.txt: cat b.txt\n",
+        );
+
+        assert!(matches!(
+            execute(&dir, UpToDate::Timestamps),
+            Err(BuildError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn up_to_date_from_str() {
+        assert_eq!("timestamps".parse(), Ok(UpToDate::Timestamps));
+        assert_eq!("hashes".parse(), Ok(UpToDate::ContentHashes));
+        assert!("mtime".parse::<UpToDate>().is_err());
+    }
+}
@@ -1,370 +1,2645 @@
-use std::collections::HashMap;
-use std::error::Error;
+use std::collections::{HashMap, HashSet};
 use std::iter::zip;
+use std::str::FromStr;
 
 use midlang::*;
 
+use crate::diagnostic::{Diagnostic, Diagnostics};
+
 type FuncSig<'a> = (
     &'a Visibility,
     &'a Option<Type>,
     &'a Vec<FuncArg>,
     &'a Variadic,
+    &'a Noreturn,
 );
-type FwdDecls<'a> = HashMap<&'a str, FuncSig<'a>>;
-type Res<T> = Result<T, Box<dyn Error>>;
-type Vars<'a> = HashMap<&'a str, &'a Type>;
+// The funcs a module declares itself, along with the funcs other modules keep private by the
+// module that owns them, calling one of those without a local declaration is an error
+#[derive(Default)]
+struct FwdDecls<'a> {
+    sigs: HashMap<&'a str, FuncSig<'a>>,
+    privates: HashMap<&'a str, &'a str>,
+}
+type Res<T> = Result<T, Diagnostic>;
+// The funcs each module defines, in module order so diagnostics name modules deterministically
+type Symbols<'a> = Vec<(&'a str, Vec<(&'a str, FuncSig<'a>)>)>;
+type Structs<'a> = HashMap<&'a str, &'a Vec<StructField>>;
+type Global = bool;
+type Mutable = bool;
+type Vars<'a> = HashMap<&'a str, (&'a Type, Mutable, Global)>;
 
-pub fn type_check(modules: &[Module]) -> Res<()> {
-    for module in modules {
-        check_decls(&module.decls)?;
-    }
+// How a rule reports what it finds, rules that lead to broken output are always errors
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Error,
+}
 
-    Ok(())
+impl Level {
+    fn report(
+        self,
+        error_code: &'static str,
+        warning_code: &'static str,
+        message: String,
+    ) -> Option<Diagnostic> {
+        match self {
+            Level::Allow => None,
+            Level::Warn => Some(Diagnostic::warning(warning_code, message)),
+            Level::Error => Some(Diagnostic::error(error_code, message)),
+        }
+    }
 }
 
-fn check_decls(decls: &[Decl]) -> Res<()> {
-    fn variadic_err(name: &str) -> Res<()> {
-        Err(format!(
-            "Func '{}' requires at least one argument since it is variadic",
-            name
-        )
-        .into())
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Level::Allow),
+            "warn" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            _ => Err(format!(
+                "Unknown level '{}', expected allow, warn or error",
+                s
+            )),
+        }
     }
+}
 
-    let mut fwd_decls = FwdDecls::with_capacity(decls.len());
+// A second FuncDecl of the same name in one module is not a rule, QBE rejects the IL it leads to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rules {
+    // A VarDecl or VarDeclMut of a name an arg or earlier var already binds, in the same scope or
+    // an enclosing one and with any type. Shadowing a global is always an error
+    pub shadowing: Level,
+}
 
-    for decl in decls {
-        match decl {
-            Decl::FwdDecl(name, visibility, r#type, args, variadic) => {
-                if *variadic && args.is_empty() {
-                    return variadic_err(name);
-                }
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            shadowing: Level::Warn,
+        }
+    }
+}
 
-                fwd_decls.insert(name, (visibility, r#type, args, variadic));
-            }
-            Decl::FuncDecl(name, visibility, r#type, args, variadic, stmts) => {
-                if *variadic && args.is_empty() {
-                    return variadic_err(name);
-                }
+// Ok holds any warnings, Err holds every diagnostic with at least one error among them
+pub fn type_check(modules: &[Module], rules: Rules) -> Result<Diagnostics, Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+    let symbols = symbols(modules);
 
-                let sig = (visibility, r#type, args, variadic);
-                let fwd_sig = fwd_decls.entry(name).or_insert(sig);
+    for module in modules {
+        diagnostics.extend(within(
+            check_decls(&module.name, &module.decls, &symbols, rules),
+            &module.name,
+            None,
+        ));
+    }
 
-                if sig != *fwd_sig {
-                    return Err(format!("FwdDecl mismatch for func '{}'", name).into());
-                }
+    match diagnostics.iter().any(Diagnostic::is_error) {
+        true => Err(diagnostics),
+        false => Ok(diagnostics),
+    }
+}
 
-                let mut vars = args
-                    .iter()
-                    .map(|a| (a.0.as_ref(), &a.1))
-                    .collect::<HashMap<_, _>>();
+// The C runtime calls main so an executable needs exactly one, public and with a signature C
+// accepts. Duplicates, in one module or across them, are always errors in type_check
+pub fn check_entry_point(modules: &[Module]) -> Res<()> {
+    let mains = modules
+        .iter()
+        .flat_map(|module| module.decls.iter().map(move |decl| (module, decl)))
+        .filter_map(|(module, decl)| match decl {
+            Decl::FuncDecl(name, visibility, r#type, args, variadic, noreturn, _)
+                if name == "main" =>
+            {
+                Some((module, (visibility, r#type, args, variadic, noreturn)))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
 
-                if args.len() != vars.len() {
-                    return Err(format!("Args for func '{}' must have unique names", name).into());
-                }
+    let Some((module, (_, r#type, args, variadic, noreturn))) = mains
+        .iter()
+        .find(|(_, (visibility, _, _, _, _))| **visibility == Visibility::Public)
+    else {
+        let err = Diagnostic::error("E1029", "Executable does not define a public func 'main'");
 
-                check_stmts(stmts, r#type, &fwd_decls, &mut vars)?;
+        return Err(match mains.first() {
+            Some((module, _)) => {
+                err.with_note(format!("'main' is private in module '{}'", module.name))
             }
-        }
+            None => err,
+        });
+    };
+
+    let c_args = match &args[..] {
+        [] => true,
+        [(_, Type::Int32), (_, Type::Ptr(None))] => true,
+        [(_, Type::Int32), (_, Type::Ptr(Some(argv)))] => **argv == Type::Str,
+        _ => false,
+    };
+
+    if **r#type != Some(Type::Int32) || !c_args || **variadic || **noreturn {
+        return Err(Diagnostic::error(
+            "E1030",
+            "Func 'main' must return Int32 and take no args or an Int32 and a Ptr to Str",
+        )
+        .within("main")
+        .within(&module.name));
     }
 
     Ok(())
 }
 
-fn check_stmts<'a>(
-    stmts: &'a [Stmt],
-    func_type: &Option<Type>,
-    fwd_decls: &FwdDecls,
-    vars: &'a mut Vars<'a>,
-) -> Res<()> {
-    fn ret_type_mismatch_err() -> Res<()> {
-        Err("Return statment type does not match function type".into())
-    }
+// Diagnostics from a nested scope gain the path segment that leads to it and the loc of the node
+// it names, if that node has one
+fn within<'a>(
+    diagnostics: Diagnostics,
+    segment: &'a str,
+    loc: Option<&'a Loc>,
+) -> impl Iterator<Item = Diagnostic> + 'a {
+    diagnostics
+        .into_iter()
+        .map(move |d| d.within(segment).at(loc))
+}
 
-    for stmt in stmts {
-        match stmt {
-            Stmt::Cond(cases) => {
-                for (expr, stmts) in cases {
-                    if *expr.r#type() != Type::Bool {
-                        return Err("Cond case expressions must be of type bool".into());
+fn symbols(modules: &[Module]) -> Symbols<'_> {
+    modules
+        .iter()
+        .map(|module| {
+            let funcs = module
+                .decls
+                .iter()
+                .filter_map(|decl| match decl {
+                    Decl::FuncDecl(name, visibility, r#type, args, variadic, noreturn, _) => {
+                        Some((name as &str, (visibility, r#type, args, variadic, noreturn)))
                     }
-                    check_expr(expr, fwd_decls, vars)?;
+                    _ => None,
+                })
+                .collect();
 
-                    let mut cond_vars = vars.clone();
-                    check_stmts(stmts, func_type, fwd_decls, &mut cond_vars)?;
-                }
-            }
-            Stmt::FuncCall(_, exprs) => check_exprs(exprs, fwd_decls, vars)?,
-            Stmt::Ret(ret) => match (func_type, ret) {
-                (Some(func_type), Some(expr)) if expr.r#type() != func_type => {
-                    return ret_type_mismatch_err();
-                }
-                (Some(_), None) | (None, Some(_)) => {
-                    return ret_type_mismatch_err();
-                }
-                (Some(_), Some(expr)) => check_expr(expr, fwd_decls, vars)?,
-                (None, None) => (),
-            },
-            Stmt::VarDecl(name, expr) => {
-                check_expr(expr, fwd_decls, vars)?;
-                vars.insert(name, expr.r#type());
+            (module.name.as_ref(), funcs)
+        })
+        .collect()
+}
+
+// The first definition of a func outside of the given module, public ones take precedence since
+// they are the ones that get linked
+fn definition_elsewhere<'a>(
+    name: &str,
+    module: &str,
+    symbols: &Symbols<'a>,
+) -> Option<(&'a str, FuncSig<'a>)> {
+    let mut definitions = symbols
+        .iter()
+        .filter(|(other, _)| *other != module)
+        .flat_map(|(other, funcs)| {
+            funcs
+                .iter()
+                .filter(|(func, _)| *func == name)
+                .map(|(_, sig)| (*other, *sig))
+        })
+        .collect::<Vec<_>>();
+
+    definitions.sort_by_key(|(_, sig)| *sig.0 != Visibility::Public);
+    definitions.into_iter().next()
+}
+
+fn defines(name: &str, module: &str, symbols: &Symbols) -> bool {
+    symbols
+        .iter()
+        .any(|(other, funcs)| *other == module && funcs.iter().any(|(func, _)| *func == name))
+}
+
+// The funcs only other modules define and none of them make public, by their first owner
+fn privates<'a>(module: &str, symbols: &Symbols<'a>) -> HashMap<&'a str, &'a str> {
+    symbols
+        .iter()
+        .flat_map(|(_, funcs)| funcs.iter().map(|(func, _)| *func))
+        .filter(|func| !defines(func, module, symbols))
+        .filter_map(|func| match definition_elsewhere(func, module, symbols) {
+            Some((other, (Visibility::Private, _, _, _, _))) => Some((func, other)),
+            _ => None,
+        })
+        .collect()
+}
+
+// Checking recovers at each decl so one broken func does not hide the errors in the next
+fn check_decls<'a>(
+    module: &str,
+    decls: &'a [Decl],
+    symbols: &Symbols<'a>,
+    rules: Rules,
+) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    let mut defined = HashSet::new();
+    let mut fwd_decls = FwdDecls {
+        sigs: HashMap::with_capacity(decls.len()),
+        privates: privates(module, symbols),
+    };
+    let mut globals = Vars::new();
+    let mut structs = Structs::new();
+
+    for (loc, decl) in located(decls) {
+        let name = match decl {
+            Decl::Const(name, _, _, _)
+            | Decl::FwdDecl(name, _, _, _, _, _)
+            | Decl::FuncDecl(name, _, _, _, _, _, _)
+            | Decl::Global(name, _, _, _, _)
+            | Decl::Import(name)
+            | Decl::StructDecl(name, _) => name,
+            Decl::Loc(_) => continue,
+        };
+        let mut decl_diagnostics = Diagnostics::new();
+
+        if let Decl::FuncDecl(name, _, _, _, _, _, _) = decl {
+            if !defined.insert(name) {
+                decl_diagnostics.push(Diagnostic::error(
+                    "E1028",
+                    format!("Func '{}' is already defined", name),
+                ));
             }
         }
-    }
 
-    Ok(())
-}
+        if let Err(err) = check_decl(
+            decl,
+            module,
+            symbols,
+            rules,
+            &mut fwd_decls,
+            &mut globals,
+            &mut structs,
+            &mut decl_diagnostics,
+        ) {
+            decl_diagnostics.push(err);
+        }
 
-fn check_exprs(exprs: &[Expr], fwd_decls: &FwdDecls, vars: &Vars) -> Res<()> {
-    for expr in exprs {
-        check_expr(expr, fwd_decls, vars)?;
+        diagnostics.extend(within(decl_diagnostics, name, loc));
     }
 
-    Ok(())
+    diagnostics
 }
 
-fn check_expr(expr: &Expr, fwd_decls: &FwdDecls, vars: &Vars) -> Res<()> {
-    fn func_call_type_err(name: &str) -> Res<()> {
-        Err(format!(
-            "FuncCall '{}' type does not match forward declaration",
-            name
-        )
-        .into())
-    }
-    fn param_count_err(name: &str) -> Res<()> {
-        Err(format!(
-            "FuncCall '{}' parameter count does not match forward declaration",
-            name
-        )
-        .into())
+#[allow(clippy::too_many_arguments)]
+fn check_decl<'a>(
+    decl: &'a Decl,
+    module: &str,
+    symbols: &Symbols<'a>,
+    rules: Rules,
+    fwd_decls: &mut FwdDecls<'a>,
+    globals: &mut Vars<'a>,
+    structs: &mut Structs<'a>,
+    diagnostics: &mut Diagnostics,
+) -> Res<()> {
+    fn variadic_err(name: &str) -> Res<()> {
+        Err(Diagnostic::error(
+            "E1001",
+            format!(
+                "Func '{}' requires at least one argument since it is variadic",
+                name
+            ),
+        ))
     }
 
-    fn param_type_err(name: &str, i: usize) -> Res<()> {
-        Err(format!(
-            "FuncCall '{}' parameter {} type does not match forward declaration",
-            name, i
-        )
-        .into())
+    fn global_decl<'a>(
+        name: &'a str,
+        r#type: &'a Type,
+        expr: &Expr,
+        mutable: bool,
+        structs: &Structs,
+        globals: &mut Vars<'a>,
+    ) -> Res<()> {
+        check_type(r#type, structs)?;
+
+        if !is_const_expr(expr) {
+            return Err(Diagnostic::error(
+                "E1002",
+                format!(
+                    "Global '{}' must be initialized with a constant expression",
+                    name
+                ),
+            ));
+        }
+
+        if expr.r#type() != r#type {
+            return Err(Diagnostic::error(
+                "E1003",
+                format!(
+                    "Global '{}' initializer type does not match its declaration",
+                    name
+                ),
+            ));
+        }
+
+        check_expr(expr, &FwdDecls::default(), structs, &Vars::new())?;
+
+        match globals.insert(name, (r#type, mutable, true)) {
+            Some(_) => Err(Diagnostic::error(
+                "E1004",
+                format!("Global '{}' is already declared", name),
+            )),
+            None => Ok(()),
+        }
     }
 
-    match expr {
-        Expr::Cmp(op, lhs, rhs) => {
-            if lhs.r#type() != rhs.r#type() {
-                return Err(format!(
-                    "Comparison '{}' has different left hand and right hand side types",
-                    op
-                )
-                .into());
+    match decl {
+        Decl::Const(name, _, linkage, expr) => {
+            // Consts are read only data so there is nothing to keep per thread
+            if linkage.contains(&Linkage::Thread) {
+                return Err(Diagnostic::error(
+                    "E1005",
+                    format!("Const '{}' cannot be thread local", name),
+                ));
             }
+
+            check_linkage("Const", name, linkage)?;
+            global_decl(name, expr.r#type(), expr, false, structs, globals)?;
         }
-        Expr::ConstBool(_)
-        | Expr::ConstDouble(_)
-        | Expr::ConstInt32(_)
-        | Expr::ConstInt64(_)
-        | Expr::ConstStr(_) => (),
-        Expr::FuncCall(name, call_type, exprs) => {
-            match fwd_decls.get(&name as &str) {
-                Some((_, None, _, _)) => {
-                    return func_call_type_err(name);
-                }
-                Some((_, Some(fwd_type), _, _)) if call_type != fwd_type => {
-                    return func_call_type_err(name);
-                }
-                Some((_, _, fwd_args, false)) if exprs.len() != fwd_args.len() => {
-                    return param_count_err(name);
+        Decl::FwdDecl(name, visibility, r#type, args, variadic, noreturn) => {
+            if *variadic && args.is_empty() {
+                return variadic_err(name);
+            }
+
+            check_sig_types(name, r#type, args, *noreturn, structs)?;
+
+            let sig = (visibility, r#type, args, variadic, noreturn);
+
+            // A private func of another module is never linked against, so the FwdDecl names an
+            // external func and only public definitions have to match it
+            if let Some((other, other_sig)) = definition_elsewhere(name, module, symbols) {
+                if !defines(name, module, symbols)
+                    && *other_sig.0 == Visibility::Public
+                    && sig != other_sig
+                {
+                    return Err(Diagnostic::error(
+                        "E1023",
+                        format!(
+                            "FwdDecl for func '{}' does not match its definition in module '{}'",
+                            name, other
+                        ),
+                    ));
                 }
-                Some((_, _, fwd_args, true)) if exprs.len() < fwd_args.len() => {
-                    return param_count_err(name);
+            }
+
+            fwd_decls.sigs.insert(name, sig);
+        }
+        Decl::FuncDecl(name, visibility, r#type, args, variadic, noreturn, stmts) => {
+            if *variadic && args.is_empty() {
+                return variadic_err(name);
+            }
+
+            check_sig_types(name, r#type, args, *noreturn, structs)?;
+
+            let sig = (visibility, r#type, args, variadic, noreturn);
+            let fwd_sig = fwd_decls.sigs.entry(name).or_insert(sig);
+
+            if sig != *fwd_sig {
+                return Err(Diagnostic::error(
+                    "E1006",
+                    format!("FwdDecl mismatch for func '{}'", name),
+                ));
+            }
+
+            if *visibility == Visibility::Public {
+                if let Some((other, (Visibility::Public, _, _, _, _))) =
+                    definition_elsewhere(name, module, symbols)
+                {
+                    return Err(Diagnostic::error(
+                        "E1024",
+                        format!(
+                            "Public func '{}' is also defined in module '{}'",
+                            name, other
+                        ),
+                    ));
                 }
-                Some((_, _, fwd_args, _)) => {
-                    for (i, ((_, r#type), expr)) in zip(*fwd_args, exprs).enumerate() {
-                        match (r#type, expr) {
-                            (Type::Ptr(Some(r#type)), Expr::VarRef(_, expr_type, true)) => {
-                                if r#type.as_ref() != expr_type {
-                                    return param_type_err(name, i);
-                                }
-                            }
-                            _ => {
-                                if r#type != expr.r#type() {
-                                    return param_type_err(name, i);
-                                }
-                            }
-                        }
-                    }
+            }
+
+            let mut vars = args
+                .iter()
+                .map(|a| (a.0.as_ref(), (&a.1, false, false)))
+                .collect::<HashMap<_, _>>();
+
+            if args.len() != vars.len() {
+                return Err(Diagnostic::error(
+                    "E1007",
+                    format!("Args for func '{}' must have unique names", name),
+                ));
+            }
+
+            for (arg_name, _) in args {
+                if globals.contains_key(arg_name as &str) {
+                    return Err(Diagnostic::error(
+                        "E1008",
+                        format!(
+                            "Arg '{}' for func '{}' shadows a global of the same name",
+                            arg_name, name
+                        ),
+                    ));
                 }
-                None => {
-                    return Err(format!(
-                        "Calling func '{}' which does not have a forward declaration",
+            }
+
+            vars.extend(globals.iter());
+
+            diagnostics.extend(check_stmts(
+                stmts, r#type, false, rules, fwd_decls, structs, &mut vars,
+            ));
+
+            if *noreturn && has_ret(stmts) {
+                return Err(Diagnostic::error(
+                    "E1009",
+                    format!("Noreturn func '{}' cannot return", name),
+                ));
+            }
+
+            if *noreturn && !terminates(stmts, fwd_decls) {
+                return Err(Diagnostic::error(
+                    "E1010",
+                    format!("Noreturn func '{}' can reach the end of its body", name),
+                ));
+            }
+
+            if r#type.is_some() && !terminates(stmts, fwd_decls) {
+                return Err(Diagnostic::error(
+                    "E1011",
+                    format!(
+                        "Func '{}' can reach the end of its body without returning a value",
                         name
-                    )
-                    .into());
-                }
+                    ),
+                ));
             }
 
-            check_exprs(exprs, fwd_decls, vars)?;
+            diagnostics.extend(unreachable_stmt(name, stmts, fwd_decls));
         }
-        Expr::Not(expr) => {
-            if *expr.r#type() != Type::Bool {
-                return Err("Expression passed to not must be of type bool"
-                    .to_string()
-                    .into());
+        Decl::Global(name, _, linkage, r#type, Some(expr)) => {
+            check_linkage("Global", name, linkage)?;
+            global_decl(name, r#type, expr, true, structs, globals)?;
+        }
+        Decl::Global(name, _, linkage, r#type, None) => {
+            check_linkage("Global", name, linkage)?;
+            check_type(r#type, structs)?;
+
+            if globals.insert(name, (r#type, true, true)).is_some() {
+                return Err(Diagnostic::error(
+                    "E1004",
+                    format!("Global '{}' is already declared", name),
+                ));
             }
         }
-        Expr::VarRef(name, r#type, _) => match vars.get(&name as &str) {
-            Some(expr_type) if *expr_type != r#type => {
-                return Err(format!("VarRef '{}' type does not match its declaration", name).into())
+        Decl::Import(name) => {
+            let Some((_, funcs)) = symbols.iter().find(|(other, _)| other == name) else {
+                return Err(Diagnostic::error(
+                    "E1025",
+                    format!("Imported module '{}' does not exist", name),
+                ));
+            };
+
+            if name == module {
+                return Err(Diagnostic::error(
+                    "E1026",
+                    format!("Module '{}' cannot import itself", name),
+                ));
             }
-            Some(_) => (),
-            None => return Err(format!("VarRef '{}' does not have a declaration", name).into()),
-        },
-    }
 
-    Ok(())
-}
+            for (func, sig) in funcs {
+                if *sig.0 != Visibility::Public {
+                    continue;
+                }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+                let fwd_sig = fwd_decls.sigs.entry(func).or_insert(*sig);
 
-    type TestResult = Res<()>;
+                if sig != fwd_sig {
+                    return Err(Diagnostic::error(
+                        "E1027",
+                        format!(
+                            "Func '{}' imported from module '{}' does not match its local declaration",
+                            func, name
+                        ),
+                    ));
+                }
+            }
+        }
+        Decl::Loc(_) => (),
+        Decl::StructDecl(name, fields) => {
+            if structs.contains_key(name as &str) {
+                return Err(Diagnostic::error(
+                    "E1012",
+                    format!("Struct '{}' is already declared", name),
+                ));
+            }
 
-    #[test]
-    fn hello_world() -> TestResult {
-        let modules = mtc::hello_world();
+            if fields.is_empty() {
+                return Err(Diagnostic::error(
+                    "E1013",
+                    format!("Struct '{}' must have at least one field", name),
+                ));
+            }
 
-        type_check(&modules)?;
+            let field_names = fields.iter().map(|f| &f.0).collect::<HashSet<_>>();
 
-        Ok(())
-    }
+            if fields.len() != field_names.len() {
+                return Err(Diagnostic::error(
+                    "E1014",
+                    format!("Fields for struct '{}' must have unique names", name),
+                ));
+            }
 
-    #[test]
-    fn cond() -> TestResult {
-        let modules = mtc::cond();
+            for (field_name, r#type) in fields {
+                if let Type::Array(_, _) = r#type {
+                    return Err(Diagnostic::error(
+                        "E1015",
+                        format!(
+                            "Struct '{}' field '{}' cannot be of an array type",
+                            name, field_name
+                        ),
+                    ));
+                }
 
-        type_check(&modules)?;
+                check_type(r#type, structs)?;
+            }
 
-        Ok(())
+            structs.insert(name, fields);
+        }
     }
 
-    #[test]
-    fn nested_func_call() -> Res<()> {
-        let modules = mtc::nested_func_call();
+    Ok(())
+}
 
-        type_check(&modules)?;
+fn check_linkage(kind: &str, name: &str, linkage: &[Linkage]) -> Res<()> {
+    let mut sections = 0;
+    let mut thread = false;
 
-        Ok(())
+    for l in linkage {
+        match l {
+            Linkage::Section(section, _) if section.is_empty() => {
+                return Err(Diagnostic::error(
+                    "E1016",
+                    format!("{} '{}' section name cannot be empty", kind, name),
+                ));
+            }
+            Linkage::Section(_, _) => sections += 1,
+            Linkage::Thread if thread => {
+                return Err(Diagnostic::error(
+                    "E1017",
+                    format!("{} '{}' is marked thread local more than once", kind, name),
+                ));
+            }
+            Linkage::Thread => thread = true,
+        }
     }
 
-    #[test]
-    fn func_call_variadic_params_just_one() -> TestResult {
-        let modules = mtc::func_call_variadic_params_just_one();
+    if sections > 1 {
+        return Err(Diagnostic::error(
+            "E1018",
+            format!(
+                "{} '{}' cannot be placed in more than one section",
+                kind, name
+            ),
+        ));
+    }
 
-        type_check(&modules)?;
+    Ok(())
+}
 
-        Ok(())
+fn check_sig_types(
+    name: &str,
+    r#type: &Option<Type>,
+    args: &[FuncArg],
+    noreturn: Noreturn,
+    structs: &Structs,
+) -> Res<()> {
+    if noreturn && r#type.is_some() {
+        return Err(Diagnostic::error(
+            "E1019",
+            format!("Noreturn func '{}' cannot have a return type", name),
+        ));
     }
 
-    #[test]
-    fn func_call_variadic_params_multiple() -> TestResult {
-        let modules = mtc::func_call_variadic_params_multiple();
-
-        type_check(&modules)?;
+    for r#type in r#type.iter().chain(args.iter().map(|a| &a.1)) {
+        if let Type::Array(_, _) = r#type {
+            return Err(Diagnostic::error(
+                "E1020",
+                format!(
+                    "Func '{}' cannot take or return an array type, use a pointer instead",
+                    name
+                ),
+            ));
+        }
 
-        Ok(())
+        check_type(r#type, structs)?;
     }
 
-    #[test]
-    fn var_ref() -> TestResult {
-        let modules = mtc::var_ref();
+    Ok(())
+}
 
-        type_check(&modules)?;
+// Stmts terminate when control can never reach past their end, either by returning, by calling
+// a func that never returns or by a cond or loop that cannot be left any other way
+fn terminates(stmts: &[Stmt], fwd_decls: &FwdDecls) -> bool {
+    located(stmts).any(|(_, stmt)| stmt_terminates(stmt, fwd_decls))
+}
 
-        Ok(())
+fn stmt_terminates(stmt: &Stmt, fwd_decls: &FwdDecls) -> bool {
+    match stmt {
+        Stmt::Cond(cases) => {
+            // Only the cases up to the first one that always matches can run, without such a
+            // case control can fall through the cond
+            match cases
+                .iter()
+                .position(|(expr, _)| matches!(expr, Expr::ConstBool(true)))
+            {
+                Some(i) => cases[..=i]
+                    .iter()
+                    .all(|(_, stmts)| terminates(stmts, fwd_decls)),
+                None => false,
+            }
+        }
+        Stmt::FuncCall(name, _) => fwd_decls
+            .sigs
+            .get(name as &str)
+            .is_some_and(|(_, _, _, _, noreturn)| **noreturn),
+        Stmt::Ret(_) | Stmt::Unreachable => true,
+        Stmt::While(Expr::ConstBool(true), stmts) => !has_break(stmts),
+        _ => false,
     }
+}
 
-    #[test]
-    fn void_main() -> TestResult {
-        let modules = mtc::void_main();
-
-        type_check(&modules)?;
+// Breaks in nested loops leave those loops, not the one being checked
+fn has_break(stmts: &[Stmt]) -> bool {
+    located(stmts).any(|(_, stmt)| match stmt {
+        Stmt::Break => true,
+        Stmt::Cond(cases) => cases.iter().any(|(_, stmts)| has_break(stmts)),
+        _ => false,
+    })
+}
 
-        Ok(())
-    }
+// Warns at the first stmt in source order that cannot be reached, any stmt after one that leaves
+// its block is unreachable
+fn unreachable_stmt(name: &str, stmts: &[Stmt], fwd_decls: &FwdDecls) -> Option<Diagnostic> {
+    let leaves = |stmt: &Stmt| {
+        matches!(stmt, Stmt::Break | Stmt::Continue) || stmt_terminates(stmt, fwd_decls)
+    };
+    let mut left = false;
 
-    #[test]
-    fn fabs() -> TestResult {
-        let modules = mtc::fabs();
+    for (i, (loc, stmt)) in located(stmts).enumerate() {
+        let segment = format!("stmt {}", i);
 
-        type_check(&modules)?;
+        if left {
+            let warning = Diagnostic::warning(
+                "W1001",
+                format!("Func '{}' has unreachable statements", name),
+            );
 
-        Ok(())
-    }
+            return Some(warning.within(segment).at(loc));
+        }
 
-    #[test]
-    fn frexp() -> TestResult {
-        let modules = mtc::frexp();
+        let nested = match stmt {
+            Stmt::Cond(cases) => cases.iter().enumerate().find_map(|(i, (_, stmts))| {
+                unreachable_stmt(name, stmts, fwd_decls).map(|d| d.within(format!("case {}", i)))
+            }),
+            Stmt::While(_, stmts) => unreachable_stmt(name, stmts, fwd_decls),
+            _ => None,
+        };
 
-        type_check(&modules)?;
+        if let Some(warning) = nested {
+            return Some(warning.within(segment).at(loc));
+        }
 
-        Ok(())
+        left = leaves(stmt);
     }
 
-    #[test]
-    fn cmp() -> TestResult {
-        let modules = mtc::cmp();
+    None
+}
 
-        type_check(&modules)?;
+fn has_ret(stmts: &[Stmt]) -> bool {
+    located(stmts).any(|(_, stmt)| match stmt {
+        Stmt::Cond(cases) => cases.iter().any(|(_, stmts)| has_ret(stmts)),
+        Stmt::Ret(_) => true,
+        Stmt::While(_, stmts) => has_ret(stmts),
+        _ => false,
+    })
+}
 
-        Ok(())
+fn check_type(r#type: &Type, structs: &Structs) -> Res<()> {
+    match r#type {
+        Type::Array(_, 0) => Err(Diagnostic::error(
+            "E1021",
+            "Array type must have a length greater than zero",
+        )),
+        Type::Array(r#type, _) | Type::Ptr(Some(r#type)) => check_type(r#type, structs),
+        Type::Struct(name) if !structs.contains_key(name as &str) => Err(Diagnostic::error(
+            "E1022",
+            format!("Struct '{}' does not have a declaration", name),
+        )),
+        _ => Ok(()),
     }
+}
 
-    #[test]
-    fn not() -> TestResult {
-        let modules = mtc::not();
+fn check_stmts<'a>(
+    stmts: &'a [Stmt],
+    func_type: &Option<Type>,
+    in_loop: bool,
+    rules: Rules,
+    fwd_decls: &FwdDecls,
+    structs: &Structs,
+    vars: &mut Vars<'a>,
+) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
 
-        type_check(&modules)?;
+    // Checking recovers at each stmt, nested stmts report their own diagnostics which come before
+    // any raised by the stmt that contains them. Locs are not counted so stmt indexes match the
+    // source the frontend lowered
+    for (i, (loc, stmt)) in located(stmts).enumerate() {
+        let mut stmt_diagnostics = Diagnostics::new();
 
-        Ok(())
+        if let Err(err) = check_stmt(
+            stmt,
+            func_type,
+            in_loop,
+            rules,
+            fwd_decls,
+            structs,
+            vars,
+            &mut stmt_diagnostics,
+        ) {
+            stmt_diagnostics.push(err);
+        }
+
+        diagnostics.extend(within(stmt_diagnostics, &format!("stmt {}", i), loc));
     }
 
-    #[test]
-    #[should_panic(expected = "FwdDecl mismatch for func 'main'")]
-    fn func_decl_fwd_decl_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Str),
-                    vec![("s".to_string(), Type::Str)],
-                    false,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
-                ),
-            ],
-        }];
+    diagnostics
+}
 
-        type_check(&modules).unwrap();
+#[allow(clippy::too_many_arguments)]
+fn check_stmt<'a>(
+    stmt: &'a Stmt,
+    func_type: &Option<Type>,
+    in_loop: bool,
+    rules: Rules,
+    fwd_decls: &FwdDecls,
+    structs: &Structs,
+    vars: &mut Vars<'a>,
+    diagnostics: &mut Diagnostics,
+) -> Res<()> {
+    fn ret_type_mismatch_err() -> Res<()> {
+        Err(Diagnostic::error(
+            "E2001",
+            "Return statment type does not match function type",
+        ))
+    }
+
+    fn var_decl<'a>(
+        name: &'a str,
+        expr: &'a Expr,
+        mutable: bool,
+        shadowing: Level,
+        vars: &mut Vars<'a>,
+        diagnostics: &mut Diagnostics,
+    ) -> Res<()> {
+        match vars.insert(name, (expr.r#type(), mutable, false)) {
+            Some((_, _, true)) => Err(Diagnostic::error(
+                "E2002",
+                format!("Var '{}' shadows a global of the same name", name),
+            )),
+            Some(_) => match shadowing.report(
+                "E2015",
+                "W2001",
+                format!("Var '{}' shadows an earlier binding of the same name", name),
+            ) {
+                Some(err) if err.is_error() => Err(err),
+                warning => {
+                    diagnostics.extend(warning);
+                    Ok(())
+                }
+            },
+            None => Ok(()),
+        }
+    }
+
+    match stmt {
+        Stmt::Assign(name, expr) => {
+            match vars.get(name as &str) {
+                Some((r#type, true, _)) if *r#type != expr.r#type() => {
+                    return Err(Diagnostic::error(
+                        "E2004",
+                        format!("Assign to '{}' type does not match its declaration", name),
+                    ));
+                }
+                Some((_, true, _)) => (),
+                Some((_, false, _)) => {
+                    return Err(Diagnostic::error(
+                        "E2005",
+                        format!("Assign to immutable var '{}'", name),
+                    ));
+                }
+                None => {
+                    return Err(Diagnostic::error(
+                        "E2006",
+                        format!("Assign to '{}' which does not have a declaration", name),
+                    ));
+                }
+            }
+
+            check_expr(expr, fwd_decls, structs, vars)?;
+        }
+        Stmt::Break if !in_loop => {
+            return Err(Diagnostic::error(
+                "E2007",
+                "Break statement must be inside a loop",
+            ));
+        }
+        Stmt::Break => (),
+        Stmt::Cond(cases) => {
+            for (i, (expr, stmts)) in cases.iter().enumerate() {
+                if *expr.r#type() != Type::Bool {
+                    return Err(Diagnostic::error(
+                        "E2008",
+                        "Cond case expressions must be of type bool",
+                    ));
+                }
+                check_expr(expr, fwd_decls, structs, vars)?;
+
+                let mut cond_vars = vars.clone();
+                let case_diagnostics = check_stmts(
+                    stmts,
+                    func_type,
+                    in_loop,
+                    rules,
+                    fwd_decls,
+                    structs,
+                    &mut cond_vars,
+                );
+
+                diagnostics.extend(within(case_diagnostics, &format!("case {}", i), None));
+            }
+        }
+        Stmt::Continue if !in_loop => {
+            return Err(Diagnostic::error(
+                "E2009",
+                "Continue statement must be inside a loop",
+            ));
+        }
+        Stmt::Continue => (),
+        Stmt::FuncCall(name, exprs) => {
+            check_func_call(name, exprs, fwd_decls)?;
+            check_exprs(exprs, fwd_decls, structs, vars)?;
+        }
+        Stmt::IndexAssign(name, index, expr) => {
+            let elem_type = match vars.get(name as &str) {
+                Some((Type::Array(_, _), false, _)) => {
+                    return Err(Diagnostic::error(
+                        "E2005",
+                        format!("Assign to immutable var '{}'", name),
+                    ));
+                }
+                Some((Type::Array(elem_type, len), true, _)) => {
+                    check_index(index, Some(*len))?;
+                    elem_type
+                }
+                Some((Type::Ptr(Some(elem_type)), _, _)) => {
+                    check_index(index, None)?;
+                    elem_type
+                }
+                Some(_) => {
+                    return Err(Diagnostic::error(
+                        "E2010",
+                        format!(
+                            "Index assign to '{}' requires a var of an array or pointer type",
+                            name
+                        ),
+                    ));
+                }
+                None => {
+                    return Err(Diagnostic::error(
+                        "E2006",
+                        format!("Assign to '{}' which does not have a declaration", name),
+                    ));
+                }
+            };
+
+            if elem_type.as_ref() != expr.r#type() {
+                return Err(Diagnostic::error(
+                    "E2011",
+                    format!(
+                        "Index assign to '{}' type does not match its element type",
+                        name
+                    ),
+                ));
+            }
+
+            check_expr(index, fwd_decls, structs, vars)?;
+            check_expr(expr, fwd_decls, structs, vars)?;
+        }
+        Stmt::Ret(ret) => match (func_type, ret) {
+            (Some(func_type), Some(expr)) if expr.r#type() != func_type => {
+                return ret_type_mismatch_err();
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                return ret_type_mismatch_err();
+            }
+            (Some(_), Some(expr)) => check_expr(expr, fwd_decls, structs, vars)?,
+            (None, None) => (),
+        },
+        Stmt::Store(ptr, expr) => {
+            let Type::Ptr(Some(r#type)) = ptr.r#type() else {
+                return Err(Diagnostic::error(
+                    "E2012",
+                    "Store requires an expression of a typed pointer type",
+                ));
+            };
+
+            if r#type.as_ref() != expr.r#type() {
+                return Err(Diagnostic::error(
+                    "E2013",
+                    "Store value type does not match the pointer type",
+                ));
+            }
+
+            check_expr(ptr, fwd_decls, structs, vars)?;
+            check_expr(expr, fwd_decls, structs, vars)?;
+        }
+        Stmt::Loc(_) | Stmt::Unreachable => (),
+        // Vars are declared even when their expr fails to check so uses of them further down do
+        // not report errors of their own
+        Stmt::VarDecl(name, expr) => {
+            let checked = check_expr(expr, fwd_decls, structs, vars);
+            var_decl(name, expr, false, rules.shadowing, vars, diagnostics)?;
+            checked?;
+        }
+        Stmt::VarDeclMut(name, expr) => {
+            let checked = check_expr(expr, fwd_decls, structs, vars);
+            var_decl(name, expr, true, rules.shadowing, vars, diagnostics)?;
+            checked?;
+        }
+        Stmt::While(expr, stmts) => {
+            if *expr.r#type() != Type::Bool {
+                return Err(Diagnostic::error(
+                    "E2014",
+                    "While expressions must be of type bool",
+                ));
+            }
+            check_expr(expr, fwd_decls, structs, vars)?;
+
+            let mut while_vars = vars.clone();
+            diagnostics.extend(check_stmts(
+                stmts,
+                func_type,
+                true,
+                rules,
+                fwd_decls,
+                structs,
+                &mut while_vars,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_exprs(exprs: &[Expr], fwd_decls: &FwdDecls, structs: &Structs, vars: &Vars) -> Res<()> {
+    for expr in exprs {
+        check_expr(expr, fwd_decls, structs, vars)?;
+    }
+
+    Ok(())
+}
+
+// Checks the args of a call against the callee's forward declaration, the return type is left to
+// the caller since calls made as statements discard it
+fn check_func_call(name: &str, exprs: &[Expr], fwd_decls: &FwdDecls) -> Res<()> {
+    fn param_count_err(name: &str) -> Res<()> {
+        Err(Diagnostic::error(
+            "E3001",
+            format!(
+                "FuncCall '{}' parameter count does not match forward declaration",
+                name
+            ),
+        ))
+    }
+
+    fn param_type_err(name: &str, i: usize) -> Diagnostic {
+        Diagnostic::error(
+            "E3002",
+            format!(
+                "FuncCall '{}' parameter {} type does not match forward declaration",
+                name, i
+            ),
+        )
+    }
+
+    match fwd_decls.sigs.get(name) {
+        Some((_, _, fwd_args, false, _)) if exprs.len() != fwd_args.len() => param_count_err(name),
+        Some((_, _, fwd_args, true, _)) if exprs.len() < fwd_args.len() => param_count_err(name),
+        Some((_, _, fwd_args, _, _)) => {
+            for (i, ((_, r#type), expr)) in zip(*fwd_args, exprs).enumerate() {
+                match (r#type, expr) {
+                    (Type::Ptr(Some(r#type)), Expr::VarRef(_, expr_type, true)) => {
+                        let decays = match expr_type {
+                            Type::Array(elem_type, _) => elem_type == r#type,
+                            _ => false,
+                        };
+
+                        if r#type.as_ref() != expr_type && !decays {
+                            return Err(param_type_err(name, i));
+                        }
+                    }
+                    _ => {
+                        if r#type != expr.r#type() {
+                            return Err(param_type_err(name, i).with_note(format!(
+                                "expected {:?}, found {:?}",
+                                r#type,
+                                expr.r#type()
+                            )));
+                        }
+                    }
+                }
+            }
+
+            Ok(())
+        }
+        None => match fwd_decls.privates.get(name) {
+            Some(owner) => Err(Diagnostic::error(
+                "E3038",
+                format!(
+                    "Calling func '{}' which is private to module '{}'",
+                    name, owner
+                ),
+            )),
+            None => Err(Diagnostic::error(
+                "E3003",
+                format!(
+                    "Calling func '{}' which does not have a forward declaration",
+                    name
+                ),
+            )),
+        },
+    }
+}
+
+fn check_expr(expr: &Expr, fwd_decls: &FwdDecls, structs: &Structs, vars: &Vars) -> Res<()> {
+    fn func_call_type_err(name: &str) -> Res<()> {
+        Err(Diagnostic::error(
+            "E3004",
+            format!(
+                "FuncCall '{}' type does not match forward declaration",
+                name
+            ),
+        ))
+    }
+
+    match expr {
+        Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+            if *lhs.r#type() != Type::Bool || *rhs.r#type() != Type::Bool {
+                let op = match expr {
+                    Expr::And(_, _) => "and",
+                    _ => "or",
+                };
+
+                return Err(Diagnostic::error(
+                    "E3005",
+                    format!("Expressions passed to {} must be of type bool", op),
+                ));
+            }
+
+            check_expr(lhs, fwd_decls, structs, vars)?;
+            check_expr(rhs, fwd_decls, structs, vars)?;
+        }
+        Expr::Arith(op, lhs, rhs) => {
+            if lhs.r#type() != rhs.r#type() {
+                return Err(Diagnostic::error(
+                    "E3006",
+                    format!(
+                        "Arithmetic '{}' has different left hand and right hand side types",
+                        op
+                    ),
+                ));
+            }
+
+            match (op, lhs.r#type()) {
+                (ArithOp::Rem, r#type) if r#type.is_int() => (),
+                (ArithOp::Rem, _) => {
+                    return Err(Diagnostic::error(
+                        "E3007",
+                        "Arithmetic 'rem' requires operands of an integer type",
+                    ));
+                }
+                (_, r#type) if r#type.is_number() => (),
+                _ => {
+                    return Err(Diagnostic::error(
+                        "E3008",
+                        format!(
+                            "Arithmetic '{}' requires operands of an integer or float type",
+                            op
+                        ),
+                    ));
+                }
+            }
+
+            check_expr(lhs, fwd_decls, structs, vars)?;
+            check_expr(rhs, fwd_decls, structs, vars)?;
+        }
+        Expr::ArrayLit(r#type, exprs) => {
+            let Type::Array(elem_type, len) = r#type else {
+                return Err(Diagnostic::error(
+                    "E3009",
+                    "Array literal must be of an array type",
+                ));
+            };
+
+            check_type(r#type, structs)?;
+
+            if exprs.len() != *len {
+                return Err(Diagnostic::error(
+                    "E3010",
+                    format!(
+                        "Array literal has {} elements but its type requires {}",
+                        exprs.len(),
+                        len
+                    ),
+                ));
+            }
+
+            for (i, expr) in exprs.iter().enumerate() {
+                if expr.r#type() != elem_type.as_ref() {
+                    return Err(Diagnostic::error(
+                        "E3011",
+                        format!(
+                            "Array literal element {} type does not match its declaration",
+                            i
+                        ),
+                    ));
+                }
+            }
+
+            check_exprs(exprs, fwd_decls, structs, vars)?;
+        }
+        Expr::Bitwise(op, lhs, rhs) => {
+            if lhs.r#type() != rhs.r#type() {
+                return Err(Diagnostic::error(
+                    "E3012",
+                    format!(
+                        "Bitwise '{}' has different left hand and right hand side types",
+                        op
+                    ),
+                ));
+            }
+
+            if !lhs.r#type().is_int() {
+                return Err(Diagnostic::error(
+                    "E3013",
+                    format!("Bitwise '{}' requires operands of an integer type", op),
+                ));
+            }
+
+            check_expr(lhs, fwd_decls, structs, vars)?;
+            check_expr(rhs, fwd_decls, structs, vars)?;
+        }
+        Expr::Cast(r#type, expr) => {
+            match (expr.r#type(), r#type) {
+                (from, to) if from == to => (),
+                (Type::Bool, to) if to.is_int() => (),
+                (from, Type::Bool) if from.is_int() => (),
+                (from, to) if from.is_number() && to.is_number() => (),
+                (from, to) => {
+                    return Err(Diagnostic::error(
+                        "E3014",
+                        format!("Cast from {:?} to {:?} is not supported", from, to),
+                    ));
+                }
+            }
+
+            check_expr(expr, fwd_decls, structs, vars)?;
+        }
+        Expr::Cmp(op, lhs, rhs) => {
+            if lhs.r#type() != rhs.r#type() {
+                return Err(Diagnostic::error(
+                    "E3015",
+                    format!(
+                        "Comparison '{}' has different left hand and right hand side types",
+                        op
+                    ),
+                ));
+            }
+
+            match (op, lhs.r#type()) {
+                (Op::Eq | Op::Ne, Type::Struct(_)) => {
+                    return Err(Diagnostic::error(
+                        "E3016",
+                        format!(
+                            "Comparison '{}' does not support operands of a struct type",
+                            op
+                        ),
+                    ));
+                }
+                (Op::Eq | Op::Ne, Type::Array(_, _)) => {
+                    return Err(Diagnostic::error(
+                        "E3017",
+                        format!(
+                            "Comparison '{}' does not support operands of an array type",
+                            op
+                        ),
+                    ));
+                }
+                (Op::Eq | Op::Ne, _) => (),
+                (Op::Lt | Op::Le | Op::Gt | Op::Ge, r#type) if r#type.is_number() => (),
+                (Op::ULt | Op::ULe | Op::UGt | Op::UGe, r#type) if r#type.is_int() => (),
+                (Op::ULt | Op::ULe | Op::UGt | Op::UGe, _) => {
+                    return Err(Diagnostic::error(
+                        "E3018",
+                        format!("Comparison '{}' requires operands of an integer type", op),
+                    ));
+                }
+                _ => {
+                    return Err(Diagnostic::error(
+                        "E3019",
+                        format!(
+                            "Comparison '{}' requires operands of an integer or float type",
+                            op
+                        ),
+                    ));
+                }
+            }
+
+            check_expr(lhs, fwd_decls, structs, vars)?;
+            check_expr(rhs, fwd_decls, structs, vars)?;
+        }
+        Expr::ConstBool(_)
+        | Expr::ConstDouble(_)
+        | Expr::ConstFloat32(_)
+        | Expr::ConstInt8(_)
+        | Expr::ConstInt16(_)
+        | Expr::ConstInt32(_)
+        | Expr::ConstInt64(_)
+        | Expr::ConstStr(_)
+        | Expr::ConstUInt8(_)
+        | Expr::ConstUInt16(_)
+        | Expr::ConstUInt32(_)
+        | Expr::ConstUInt64(_) => (),
+        Expr::Field(expr, name, r#type) => {
+            let Type::Struct(struct_name) = expr.r#type() else {
+                return Err(Diagnostic::error(
+                    "E3020",
+                    format!(
+                        "Field access '{}' requires an expression of a struct type",
+                        name
+                    ),
+                ));
+            };
+
+            match structs
+                .get(struct_name as &str)
+                .and_then(|fields| fields.iter().find(|f| f.0 == *name))
+            {
+                Some((_, field_type)) if field_type != r#type => {
+                    return Err(Diagnostic::error(
+                        "E3021",
+                        format!(
+                            "Field '{}' type does not match its declaration in struct '{}'",
+                            name, struct_name
+                        ),
+                    ));
+                }
+                Some(_) => (),
+                None => {
+                    return Err(Diagnostic::error(
+                        "E3022",
+                        format!("Struct '{}' does not have a field '{}'", struct_name, name),
+                    ));
+                }
+            }
+
+            check_expr(expr, fwd_decls, structs, vars)?;
+        }
+        Expr::FuncCall(name, call_type, exprs) => {
+            match fwd_decls.sigs.get(name as &str) {
+                Some((_, None, _, _, _)) => {
+                    return func_call_type_err(name);
+                }
+                Some((_, Some(fwd_type), _, _, _)) if call_type != fwd_type => {
+                    return func_call_type_err(name);
+                }
+                _ => (),
+            }
+
+            check_func_call(name, exprs, fwd_decls)?;
+            check_exprs(exprs, fwd_decls, structs, vars)?;
+        }
+        Expr::Index(expr, index, r#type) => {
+            let (elem_type, len) = match expr.r#type() {
+                Type::Array(elem_type, len) => (elem_type, Some(*len)),
+                Type::Ptr(Some(elem_type)) => (elem_type, None),
+                _ => {
+                    return Err(Diagnostic::error(
+                        "E3023",
+                        "Index requires an expression of an array or pointer type",
+                    ));
+                }
+            };
+
+            check_index(index, len)?;
+
+            if elem_type.as_ref() != r#type {
+                return Err(Diagnostic::error(
+                    "E3024",
+                    "Index type does not match its element type",
+                ));
+            }
+
+            check_expr(expr, fwd_decls, structs, vars)?;
+            check_expr(index, fwd_decls, structs, vars)?;
+        }
+        Expr::Load(expr, r#type) => {
+            let Type::Ptr(Some(ptr_type)) = expr.r#type() else {
+                return Err(Diagnostic::error(
+                    "E3025",
+                    "Load requires an expression of a typed pointer type",
+                ));
+            };
+
+            if ptr_type.as_ref() != r#type {
+                return Err(Diagnostic::error(
+                    "E3026",
+                    "Load type does not match the pointer type",
+                ));
+            }
+
+            check_expr(expr, fwd_decls, structs, vars)?;
+        }
+        Expr::Neg(expr) => {
+            let r#type = expr.r#type();
+
+            if !r#type.is_signed_int() && !r#type.is_float() {
+                return Err(Diagnostic::error(
+                    "E3027",
+                    "Expression passed to neg must be of a signed integer or float type",
+                ));
+            }
+
+            check_expr(expr, fwd_decls, structs, vars)?;
+        }
+        Expr::Not(expr) => {
+            if *expr.r#type() != Type::Bool {
+                return Err(Diagnostic::error(
+                    "E3028",
+                    "Expression passed to not must be of type bool",
+                ));
+            }
+
+            check_expr(expr, fwd_decls, structs, vars)?;
+        }
+        Expr::PtrAdd(expr, index) => {
+            let Type::Ptr(Some(_)) = expr.r#type() else {
+                return Err(Diagnostic::error(
+                    "E3029",
+                    "Pointer arithmetic requires an expression of a typed pointer type",
+                ));
+            };
+
+            check_index(index, None)?;
+            check_expr(expr, fwd_decls, structs, vars)?;
+            check_expr(index, fwd_decls, structs, vars)?;
+        }
+        Expr::StructLit(r#type, field_inits) => {
+            let Type::Struct(name) = r#type else {
+                return Err(Diagnostic::error(
+                    "E3030",
+                    "Struct literal must be of a struct type",
+                ));
+            };
+
+            let Some(fields) = structs.get(name as &str) else {
+                return Err(Diagnostic::error(
+                    "E1022",
+                    format!("Struct '{}' does not have a declaration", name),
+                ));
+            };
+
+            for (field_name, expr) in field_inits {
+                match fields.iter().find(|f| f.0 == *field_name) {
+                    Some((_, field_type)) if field_type != expr.r#type() => {
+                        return Err(Diagnostic::error(
+                            "E3031",
+                            format!(
+                            "Struct literal '{}' field '{}' type does not match its declaration",
+                            name, field_name
+                        ),
+                        ));
+                    }
+                    Some(_) => (),
+                    None => {
+                        return Err(Diagnostic::error(
+                            "E3022",
+                            format!("Struct '{}' does not have a field '{}'", name, field_name),
+                        ));
+                    }
+                }
+
+                check_expr(expr, fwd_decls, structs, vars)?;
+            }
+
+            for (field_name, _) in fields.iter() {
+                match field_inits.iter().filter(|f| f.0 == *field_name).count() {
+                    1 => (),
+                    0 => {
+                        return Err(Diagnostic::error(
+                            "E3032",
+                            format!(
+                                "Struct literal '{}' does not initialize field '{}'",
+                                name, field_name
+                            ),
+                        ));
+                    }
+                    _ => {
+                        return Err(Diagnostic::error(
+                            "E3033",
+                            format!(
+                                "Struct literal '{}' initializes field '{}' more than once",
+                                name, field_name
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        Expr::VarRef(name, r#type, _) => match vars.get(name as &str) {
+            Some((expr_type, _, _)) if *expr_type != r#type => {
+                return Err(Diagnostic::error(
+                    "E3034",
+                    format!("VarRef '{}' type does not match its declaration", name),
+                ))
+            }
+            Some(_) => (),
+            None => {
+                return Err(Diagnostic::error(
+                    "E3035",
+                    format!("VarRef '{}' does not have a declaration", name),
+                ))
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn is_const_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::ArrayLit(_, exprs) => exprs.iter().all(is_const_expr),
+        Expr::ConstBool(_)
+        | Expr::ConstDouble(_)
+        | Expr::ConstFloat32(_)
+        | Expr::ConstInt8(_)
+        | Expr::ConstInt16(_)
+        | Expr::ConstInt32(_)
+        | Expr::ConstInt64(_)
+        | Expr::ConstStr(_)
+        | Expr::ConstUInt8(_)
+        | Expr::ConstUInt16(_)
+        | Expr::ConstUInt32(_)
+        | Expr::ConstUInt64(_) => true,
+        Expr::StructLit(_, field_inits) => field_inits.iter().all(|f| is_const_expr(&f.1)),
+        _ => false,
+    }
+}
+
+fn check_index(index: &Expr, len: Option<usize>) -> Res<()> {
+    let i = match index {
+        Expr::ConstInt8(i) => Some(*i as i128),
+        Expr::ConstInt16(i) => Some(*i as i128),
+        Expr::ConstInt32(i) => Some(*i as i128),
+        Expr::ConstInt64(i) => Some(*i as i128),
+        Expr::ConstUInt8(i) => Some(*i as i128),
+        Expr::ConstUInt16(i) => Some(*i as i128),
+        Expr::ConstUInt32(i) => Some(*i as i128),
+        Expr::ConstUInt64(i) => Some(*i as i128),
+        _ => None,
+    };
+
+    if !index.r#type().is_int() {
+        return Err(Diagnostic::error(
+            "E3036",
+            "Index must be of an integer type",
+        ));
+    }
+
+    match (i, len) {
+        (Some(i), Some(len)) if i < 0 || i >= len as i128 => Err(Diagnostic::error(
+            "E3037",
+            format!(
+                "Index {} is out of bounds for an array of length {}",
+                i, len
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Severity;
+
+    type TestResult = Result<(), Diagnostics>;
+
+    fn codes_and_paths(modules: &[Module]) -> Vec<(&'static str, String)> {
+        type_check(modules, Rules::default())
+            .unwrap_err()
+            .iter()
+            .map(|d| (d.code, d.path.join(" > ")))
+            .collect()
+    }
+
+    // An unnamed module with the given decls ahead of a main that runs the given stmts
+    fn main_with(decls: Vec<Decl>, stmts: Vec<Stmt>) -> [Module; 1] {
+        let mut decls = decls;
+        decls.push(Decl::FuncDecl(
+            "main".to_string(),
+            Visibility::Public,
+            Some(Type::Int32),
+            vec![],
+            false,
+            false,
+            stmts,
+        ));
+        [Module {
+            name: "".to_string(),
+            decls,
+        }]
+    }
+
+    #[test]
+    fn hello_world() -> TestResult {
+        let modules = mtc::hello_world();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn cond() -> TestResult {
+        let modules = mtc::cond();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn nested_func_call() -> TestResult {
+        let modules = mtc::nested_func_call();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn func_call_variadic_params_just_one() -> TestResult {
+        let modules = mtc::func_call_variadic_params_just_one();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn func_call_variadic_params_multiple() -> TestResult {
+        let modules = mtc::func_call_variadic_params_multiple();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn var_ref() -> TestResult {
+        let modules = mtc::var_ref();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn void_main() -> TestResult {
+        let modules = mtc::void_main();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn fabs() -> TestResult {
+        let modules = mtc::fabs();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn frexp() -> TestResult {
+        let modules = mtc::frexp();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn cmp() -> TestResult {
+        let modules = mtc::cmp();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn not() -> TestResult {
+        let modules = mtc::not();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn add() -> TestResult {
+        let modules = mtc::add();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn sub() -> TestResult {
+        let modules = mtc::sub();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn mul() -> TestResult {
+        let modules = mtc::mul();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn div() -> TestResult {
+        let modules = mtc::div();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn rem() -> TestResult {
+        let modules = mtc::rem();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn neg() -> TestResult {
+        let modules = mtc::neg();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn ord() -> TestResult {
+        let modules = mtc::ord();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn loops() -> TestResult {
+        let modules = mtc::loops();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn assign() -> TestResult {
+        let modules = mtc::assign();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn structs() -> TestResult {
+        let modules = mtc::structs();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn arrays() -> TestResult {
+        let modules = mtc::arrays();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn strings() -> TestResult {
+        let modules = mtc::strings();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn globals() -> TestResult {
+        let modules = mtc::globals();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn casts() -> TestResult {
+        let modules = mtc::casts();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn logic() -> TestResult {
+        let modules = mtc::logic();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn bitwise() -> TestResult {
+        let modules = mtc::bitwise();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn numbers() -> TestResult {
+        let modules = mtc::numbers();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn linkage() -> TestResult {
+        let modules = mtc::linkage();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn noreturn() -> TestResult {
+        let modules = mtc::noreturn();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn loc() -> TestResult {
+        let modules = mtc::loc();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn hello_world3() -> TestResult {
+        let modules = mtc::hello_world3();
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn shared_lib() -> TestResult {
+        type_check(&mtc::shared_lib(), Rules::default())?;
+        type_check(&mtc::shared_lib_main(), Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn shadowing() -> TestResult {
+        let modules = mtc::shadowing();
+        let warnings = type_check(&modules, Rules::default())?;
+
+        assert!(warnings.iter().all(|w| w.code == "W2001"));
+        assert_eq!(warnings.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn func_decl_fwd_decl_mismatch() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Str),
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1006", " > main".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_decl_fwd_decl_mismatch2() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    None,
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1006", " > main".to_string())]
+        );
+    }
+
+    fn duplicate_main() -> [Module; 1] {
+        let main = || {
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+            )
+        };
+
+        [Module {
+            name: "".to_string(),
+            decls: vec![main(), main()],
+        }]
+    }
+
+    #[test]
+    fn func_decl_duplicate() {
+        let modules = duplicate_main();
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1028", " > main".to_string())]
+        );
+    }
+
+    fn shadowing_main() -> [Module; 1] {
+        [Module {
+            name: "".to_string(),
+            decls: vec![Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("x".to_string(), Type::Int32)],
+                false,
+                false,
+                vec![
+                    Stmt::VarDecl("x".to_string(), Expr::ConstInt32(1)),
+                    Stmt::Cond(vec![(
+                        Expr::ConstBool(true),
+                        vec![Stmt::VarDeclMut("x".to_string(), Expr::ConstInt32(2))],
+                    )]),
+                    Stmt::Ret(Some(Expr::VarRef("x".to_string(), Type::Int32, false))),
+                ],
+            )],
+        }]
+    }
+
+    #[test]
+    fn var_decl_shadowing_warn() -> TestResult {
+        let modules = shadowing_main();
+        let warnings = type_check(&modules, Rules::default())?
+            .iter()
+            .map(|d| (d.code, d.path.join(" > ")))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            warnings,
+            [
+                ("W2001", " > main > stmt 0".to_string()),
+                ("W2001", " > main > stmt 1 > case 0 > stmt 0".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn var_decl_shadowing_error() {
+        let modules = shadowing_main();
+        let rules = Rules {
+            shadowing: Level::Error,
+        };
+
+        match type_check(&modules, rules) {
+            Err(d) => assert_eq!(
+                d.iter()
+                    .map(|d| (d.code, d.path.join(" > ")))
+                    .collect::<Vec<_>>(),
+                [
+                    ("E2015", " > main > stmt 0".to_string()),
+                    ("E2015", " > main > stmt 1 > case 0 > stmt 0".to_string()),
+                ]
+            ),
+            Ok(warnings) => panic!("Expected shadowing errors, got {:?}", warnings),
+        }
+    }
+
+    #[test]
+    fn var_decl_shadowing_allow() -> TestResult {
+        let modules = shadowing_main();
+
+        assert!(type_check(
+            &modules,
+            Rules {
+                shadowing: Level::Allow
+            }
+        )?
+        .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn level_from_str() {
+        assert_eq!("allow".parse(), Ok(Level::Allow));
+        assert_eq!("warn".parse(), Ok(Level::Warn));
+        assert_eq!("error".parse(), Ok(Level::Error));
+        assert!("deny".parse::<Level>().is_err());
+    }
+
+    #[test]
+    fn func_decl_non_uniq_arg_names() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str), ("s".to_string(), Type::Str)],
+                false,
+                false,
+                vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+            )],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1007", " > main".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_no_fwd_decl() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "r".to_string(),
+                    Expr::FuncCall(
+                        "puts".to_string(),
+                        Type::Int32,
+                        vec![Expr::ConstStr("hello world".to_string())],
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3003", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_ret_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![Stmt::Ret(Some(Expr::ConstStr("hello world".to_string())))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2001", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_ret_type_mismatch2() {
+        let modules = main_with(vec![], vec![Stmt::Ret(None)]);
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2001", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn var_ref_no_decl() {
+        let modules = main_with(
+            vec![],
+            vec![Stmt::Ret(Some(Expr::VarRef(
+                "missing".to_string(),
+                Type::Int32,
+                false,
+            )))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3035", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn var_ref_decl_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("x".to_string(), Expr::ConstBool(true)),
+                Stmt::Ret(Some(Expr::VarRef("x".to_string(), Type::Int32, false))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3034", " > main > stmt 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn not_expr_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("x".to_string(), Expr::Not(Box::new(Expr::ConstInt32(3)))),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3028", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_fewer_fixed_params() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "puts".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r".to_string(),
+                            Expr::FuncCall("puts".to_string(), Type::Int32, vec![]),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3001", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_more_fixed_params() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "puts".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r".to_string(),
+                            Expr::FuncCall(
+                                "puts".to_string(),
+                                Type::Int32,
+                                vec![
+                                    Expr::ConstStr("hello world".to_string()),
+                                    Expr::ConstStr("err?".to_string()),
+                                ],
+                            ),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3001", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_fixed_param_type_mismatch() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "puts".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r".to_string(),
+                            Expr::FuncCall(
+                                "puts".to_string(),
+                                Type::Int32,
+                                vec![Expr::ConstInt32(1)],
+                            ),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3002", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_by_ref_type_mismatch() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "frexp".to_string(),
+                    Visibility::Public,
+                    Some(Type::Double),
+                    vec![
+                        ("x".to_string(), Type::Double),
+                        ("exp".to_string(), Type::Ptr(Some(Box::new(Type::Int32)))),
+                    ],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl("bad_exp".to_string(), Expr::ConstBool(false)),
+                        Stmt::VarDecl(
+                            "r".to_string(),
+                            Expr::FuncCall(
+                                "frexp".to_string(),
+                                Type::Double,
+                                vec![
+                                    Expr::ConstDouble(2560.0),
+                                    Expr::VarRef("bad_exp".to_string(), Type::Bool, true),
+                                ],
+                            ),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3002", " > main > stmt 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn nested_func_call_type_mismatch() {
+        let modules = [Module {
+            name: "hello_world".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "puts".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                ),
+                Decl::FwdDecl(
+                    "not_ok".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![Stmt::Ret(Some(Expr::FuncCall(
+                        "not_ok".to_string(),
+                        Type::Int32,
+                        vec![Expr::FuncCall(
+                            "puts".to_string(),
+                            Type::Int32,
+                            vec![Expr::ConstStr("hello world".to_string())],
+                        )],
+                    )))],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3002", "hello_world > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_variadic_params_just_one_too_few() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "printf".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![("fmt".to_string(), Type::Str)],
+                    true,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r1".to_string(),
+                            Expr::FuncCall("printf".to_string(), Type::Int32, vec![]),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3001", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn fwd_decl_variadic_no_params() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "printf".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    true,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r1".to_string(),
+                            Expr::FuncCall("printf".to_string(), Type::Int32, vec![]),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [
+                ("E1001", " > printf".to_string()),
+                ("E3003", " > main > stmt 0".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn func_decl_variadic_no_params() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "printf".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    true,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r1".to_string(),
+                            Expr::FuncCall("printf".to_string(), Type::Int32, vec![]),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1001", " > main".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_variadic_params_just_one_type_mismatch() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "printf".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![("fmt".to_string(), Type::Str)],
+                    true,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r1".to_string(),
+                            Expr::FuncCall(
+                                "printf".to_string(),
+                                Type::Int32,
+                                vec![Expr::ConstInt32(1)],
+                            ),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3002", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_variadic_params_with_many_too_few() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "printnf".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![
+                        ("fmt".to_string(), Type::Str),
+                        ("n".to_string(), Type::Int32),
+                    ],
+                    true,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r1".to_string(),
+                            Expr::FuncCall(
+                                "printnf".to_string(),
+                                Type::Int32,
+                                vec![Expr::ConstStr("hello world".to_string())],
+                            ),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3001", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_variadic_params_with_many_type_mismatch() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "printnf".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![
+                        ("fmt".to_string(), Type::Str),
+                        ("n".to_string(), Type::Int32),
+                    ],
+                    true,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r1".to_string(),
+                            Expr::FuncCall(
+                                "printnf".to_string(),
+                                Type::Int32,
+                                vec![
+                                    Expr::ConstStr("hello world".to_string()),
+                                    Expr::ConstStr("hello world".to_string()),
+                                ],
+                            ),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3002", " > main > stmt 0".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "FwdDecl mismatch for func 'main'")]
-    fn func_decl_fwd_decl_mismatch2() {
+    fn func_cond_case_not_bool() {
         let modules = [Module {
             name: "".to_string(),
             decls: vec![
                 Decl::FwdDecl(
-                    "main".to_string(),
+                    "printnf".to_string(),
                     Visibility::Public,
-                    None,
-                    vec![("s".to_string(), Type::Str)],
+                    Some(Type::Int32),
+                    vec![
+                        ("fmt".to_string(), Type::Str),
+                        ("n".to_string(), Type::Int32),
+                    ],
+                    true,
                     false,
                 ),
                 Decl::FuncDecl(
@@ -373,688 +2648,2103 @@ mod tests {
                     Some(Type::Int32),
                     vec![],
                     false,
-                    vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+                    false,
+                    vec![
+                        Stmt::Cond(vec![(
+                            Expr::ConstInt32(3),
+                            vec![Stmt::VarDecl(
+                                "r1".to_string(),
+                                Expr::FuncCall(
+                                    "printnf".to_string(),
+                                    Type::Int32,
+                                    vec![
+                                        Expr::ConstStr("hello world".to_string()),
+                                        Expr::ConstStr("hello world".to_string()),
+                                    ],
+                                ),
+                            )],
+                        )]),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2008", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn cmp_eq_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "bad_eq".to_string(),
+                    Expr::Cmp(
+                        Op::Eq,
+                        Box::new(Expr::ConstBool(true)),
+                        Box::new(Expr::ConstInt32(11)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3015", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn cmp_ne_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "bad_ne".to_string(),
+                    Expr::Cmp(
+                        Op::Ne,
+                        Box::new(Expr::ConstBool(true)),
+                        Box::new(Expr::ConstInt32(11)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3015", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn arith_add_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "bad_add".to_string(),
+                    Expr::Arith(
+                        ArithOp::Add,
+                        Box::new(Expr::ConstInt32(1)),
+                        Box::new(Expr::ConstInt64(11)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3006", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn arith_mul_bool() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "bad_mul".to_string(),
+                    Expr::Arith(
+                        ArithOp::Mul,
+                        Box::new(Expr::ConstBool(true)),
+                        Box::new(Expr::ConstBool(false)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3008", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn arith_rem_double() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "bad_rem".to_string(),
+                    Expr::Arith(
+                        ArithOp::Rem,
+                        Box::new(Expr::ConstDouble(7.5)),
+                        Box::new(Expr::ConstDouble(2.0)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3007", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn arith_operand_var_ref_no_decl() {
+        let modules = main_with(
+            vec![],
+            vec![Stmt::Ret(Some(Expr::Arith(
+                ArithOp::Sub,
+                Box::new(Expr::VarRef("missing".to_string(), Type::Int32, false)),
+                Box::new(Expr::ConstInt32(1)),
+            )))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3035", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn neg_expr_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("x".to_string(), Expr::Neg(Box::new(Expr::ConstBool(true)))),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3027", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn cmp_lt_bool() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "bad_lt".to_string(),
+                    Expr::Cmp(
+                        Op::Lt,
+                        Box::new(Expr::ConstBool(true)),
+                        Box::new(Expr::ConstBool(false)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3019", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn cmp_ult_double() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "bad_ult".to_string(),
+                    Expr::Cmp(
+                        Op::ULt,
+                        Box::new(Expr::ConstDouble(1.0)),
+                        Box::new(Expr::ConstDouble(2.0)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3018", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn cmp_ge_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "bad_ge".to_string(),
+                    Expr::Cmp(
+                        Op::Ge,
+                        Box::new(Expr::ConstInt32(1)),
+                        Box::new(Expr::ConstInt64(2)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3015", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn break_outside_loop() {
+        let modules = main_with(
+            vec![],
+            vec![Stmt::Break, Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [
+                ("E2007", " > main > stmt 0".to_string()),
+                ("W1001", " > main > stmt 1".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn continue_in_cond_outside_loop() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::Cond(vec![(Expr::ConstBool(true), vec![Stmt::Continue])]),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2009", " > main > stmt 0 > case 0 > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn while_expr_not_bool() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::While(Expr::ConstInt32(1), vec![Stmt::Break]),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2014", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn assign_immutable() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("x".to_string(), Expr::ConstInt32(0)),
+                Stmt::Assign("x".to_string(), Expr::ConstInt32(1)),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2005", " > main > stmt 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn assign_func_arg() {
+        let modules = [Module {
+            name: "".to_string(),
+            decls: vec![Decl::FuncDecl(
+                "f".to_string(),
+                Visibility::Private,
+                None,
+                vec![("a".to_string(), Type::Int32)],
+                false,
+                false,
+                vec![
+                    Stmt::Assign("a".to_string(), Expr::ConstInt32(1)),
+                    Stmt::Ret(None),
+                ],
+            )],
+        }];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2005", " > f > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn assign_no_decl() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::Assign("missing".to_string(), Expr::ConstInt32(1)),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2006", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn assign_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDeclMut("x".to_string(), Expr::ConstInt32(0)),
+                Stmt::Assign("x".to_string(), Expr::ConstInt64(1)),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2004", " > main > stmt 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn var_decl_redeclared_other_type_allow() -> TestResult {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("x".to_string(), Expr::ConstBool(true)),
+                Stmt::VarDeclMut("x".to_string(), Expr::ConstInt32(0)),
+                Stmt::Ret(Some(Expr::VarRef("x".to_string(), Type::Int32, false))),
+            ],
+        );
+        let rules = Rules {
+            shadowing: Level::Allow,
+        };
+
+        assert!(type_check(&modules, rules)?.is_empty());
+
+        let warnings = type_check(&modules, Rules::default())?
+            .iter()
+            .map(|d| (d.code, d.path.join(" > ")))
+            .collect::<Vec<_>>();
+
+        assert_eq!(warnings, [("W2001", " > main > stmt 1".to_string())]);
+
+        Ok(())
+    }
+
+    fn pt_decl() -> Decl {
+        Decl::StructDecl(
+            "pt".to_string(),
+            vec![
+                ("x".to_string(), Type::Int32),
+                ("y".to_string(), Type::Int32),
+            ],
+        )
+    }
+
+    fn pt_lit() -> Expr {
+        Expr::StructLit(
+            Type::Struct("pt".to_string()),
+            vec![
+                ("x".to_string(), Expr::ConstInt32(1)),
+                ("y".to_string(), Expr::ConstInt32(2)),
+            ],
+        )
+    }
+
+    #[test]
+    fn struct_lit_undeclared() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("p".to_string(), pt_lit()),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1022", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn struct_decl_duplicate() {
+        let modules = main_with(
+            vec![pt_decl(), pt_decl()],
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
+
+        assert_eq!(codes_and_paths(&modules), [("E1012", " > pt".to_string())]);
+    }
+
+    #[test]
+    fn struct_lit_missing_field() {
+        let modules = main_with(
+            vec![pt_decl()],
+            vec![
+                Stmt::VarDecl(
+                    "p".to_string(),
+                    Expr::StructLit(
+                        Type::Struct("pt".to_string()),
+                        vec![("x".to_string(), Expr::ConstInt32(1))],
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3032", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn struct_lit_field_type_mismatch() {
+        let modules = main_with(
+            vec![pt_decl()],
+            vec![
+                Stmt::VarDecl(
+                    "p".to_string(),
+                    Expr::StructLit(
+                        Type::Struct("pt".to_string()),
+                        vec![
+                            ("x".to_string(), Expr::ConstInt32(1)),
+                            ("y".to_string(), Expr::ConstInt64(2)),
+                        ],
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3031", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn field_access_unknown() {
+        let modules = main_with(
+            vec![pt_decl()],
+            vec![
+                Stmt::VarDecl("p".to_string(), pt_lit()),
+                Stmt::Ret(Some(Expr::Field(
+                    Box::new(Expr::VarRef(
+                        "p".to_string(),
+                        Type::Struct("pt".to_string()),
+                        false,
+                    )),
+                    "z".to_string(),
+                    Type::Int32,
+                ))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3022", " > main > stmt 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn cmp_eq_structs() {
+        let modules = main_with(
+            vec![pt_decl()],
+            vec![
+                Stmt::VarDecl(
+                    "b".to_string(),
+                    Expr::Cmp(Op::Eq, Box::new(pt_lit()), Box::new(pt_lit())),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3016", " > main > stmt 0".to_string())]
+        );
+    }
+
+    fn int32_array(len: usize) -> Type {
+        Type::Array(Box::new(Type::Int32), len)
+    }
+
+    fn xs_decl(mutable: bool) -> Stmt {
+        let lit = Expr::ArrayLit(
+            int32_array(2),
+            vec![Expr::ConstInt32(1), Expr::ConstInt32(2)],
+        );
+
+        match mutable {
+            true => Stmt::VarDeclMut("xs".to_string(), lit),
+            false => Stmt::VarDecl("xs".to_string(), lit),
+        }
+    }
+
+    fn xs_ref() -> Box<Expr> {
+        Box::new(Expr::VarRef("xs".to_string(), int32_array(2), false))
+    }
+
+    #[test]
+    fn array_lit_len_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "xs".to_string(),
+                    Expr::ArrayLit(int32_array(2), vec![Expr::ConstInt32(1)]),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3010", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn array_lit_elem_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "xs".to_string(),
+                    Expr::ArrayLit(
+                        int32_array(2),
+                        vec![Expr::ConstInt32(1), Expr::ConstInt64(2)],
+                    ),
                 ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3011", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn array_type_zero_len() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("xs".to_string(), Expr::ArrayLit(int32_array(0), vec![])),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1021", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn index_out_of_bounds() {
+        let modules = main_with(
+            vec![],
+            vec![
+                xs_decl(false),
+                Stmt::Ret(Some(Expr::Index(
+                    xs_ref(),
+                    Box::new(Expr::ConstInt32(2)),
+                    Type::Int32,
+                ))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3037", " > main > stmt 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn index_assign_negative() {
+        let modules = main_with(
+            vec![],
+            vec![
+                xs_decl(true),
+                Stmt::IndexAssign("xs".to_string(), Expr::ConstInt64(-1), Expr::ConstInt32(0)),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3037", " > main > stmt 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn index_type_not_int() {
+        let modules = main_with(
+            vec![],
+            vec![
+                xs_decl(false),
+                Stmt::Ret(Some(Expr::Index(
+                    xs_ref(),
+                    Box::new(Expr::ConstBool(true)),
+                    Type::Int32,
+                ))),
             ],
-        }];
+        );
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3036", " > main > stmt 1".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Args for func 'main' must have unique names")]
-    fn func_decl_non_uniq_arg_names() {
+    fn index_assign_immutable() {
+        let modules = main_with(
+            vec![],
+            vec![
+                xs_decl(false),
+                Stmt::IndexAssign("xs".to_string(), Expr::ConstInt32(0), Expr::ConstInt32(0)),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2005", " > main > stmt 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn load_untyped_ptr() {
         let modules = [Module {
             name: "".to_string(),
             decls: vec![Decl::FuncDecl(
-                "main".to_string(),
+                "deref".to_string(),
                 Visibility::Public,
                 Some(Type::Int32),
-                vec![("s".to_string(), Type::Str), ("s".to_string(), Type::Str)],
+                vec![("p".to_string(), Type::Ptr(None))],
                 false,
-                vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+                false,
+                vec![Stmt::Ret(Some(Expr::Load(
+                    Box::new(Expr::VarRef("p".to_string(), Type::Ptr(None), false)),
+                    Type::Int32,
+                )))],
             )],
         }];
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3025", " > deref > stmt 0".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Calling func 'puts' which does not have a forward declaration")]
-    fn func_call_no_fwd_decl() {
+    fn store_type_mismatch() {
+        let ptr_type = || Type::Ptr(Some(Box::new(Type::Int32)));
         let modules = [Module {
             name: "".to_string(),
             decls: vec![Decl::FuncDecl(
-                "main".to_string(),
+                "poke".to_string(),
                 Visibility::Public,
-                Some(Type::Int32),
-                vec![],
+                None,
+                vec![("p".to_string(), ptr_type())],
+                false,
                 false,
                 vec![
-                    Stmt::VarDecl(
-                        "r".to_string(),
-                        Expr::FuncCall(
-                            "puts".to_string(),
-                            Type::Int32,
-                            vec![Expr::ConstStr("hello world".to_string())],
-                        ),
+                    Stmt::Store(
+                        Expr::VarRef("p".to_string(), ptr_type(), false),
+                        Expr::ConstInt64(1),
                     ),
-                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    Stmt::Ret(None),
                 ],
             )],
         }];
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2013", " > poke > stmt 0".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Return statment type does not match function type")]
-    fn func_ret_type_mismatch() {
+    fn func_decl_array_arg() {
         let modules = [Module {
             name: "".to_string(),
-            decls: vec![Decl::FuncDecl(
-                "main".to_string(),
+            decls: vec![Decl::FwdDecl(
+                "sum".to_string(),
                 Visibility::Public,
                 Some(Type::Int32),
-                vec![],
+                vec![("xs".to_string(), int32_array(2))],
+                false,
                 false,
-                vec![Stmt::Ret(Some(Expr::ConstStr("hello world".to_string())))],
             )],
         }];
 
-        type_check(&modules).unwrap();
+        assert_eq!(codes_and_paths(&modules), [("E1020", " > sum".to_string())]);
+    }
+
+    fn limit_const() -> Decl {
+        Decl::Const(
+            "limit".to_string(),
+            Visibility::Private,
+            vec![],
+            Expr::ConstInt32(3),
+        )
     }
 
     #[test]
-    #[should_panic(expected = "Return statment type does not match function type")]
-    fn func_ret_type_mismatch2() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![Decl::FuncDecl(
-                "main".to_string(),
-                Visibility::Public,
-                Some(Type::Int32),
+    fn const_not_const_expr() {
+        let modules = main_with(
+            vec![Decl::Const(
+                "limit".to_string(),
+                Visibility::Private,
                 vec![],
-                false,
-                vec![Stmt::Ret(None)],
+                Expr::Neg(Box::new(Expr::ConstInt32(3))),
             )],
-        }];
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1002", " > limit".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "VarRef 'missing' does not have a declaration")]
-    fn var_ref_no_decl() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![Decl::FuncDecl(
-                "main".to_string(),
+    fn global_init_type_mismatch() {
+        let modules = main_with(
+            vec![Decl::Global(
+                "counter".to_string(),
                 Visibility::Public,
-                Some(Type::Int32),
                 vec![],
-                false,
-                vec![Stmt::Ret(Some(Expr::VarRef(
-                    "missing".to_string(),
-                    Type::Int32,
-                    false,
-                )))],
+                Type::Int32,
+                Some(Expr::ConstInt64(0)),
             )],
-        }];
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1003", " > counter".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "VarRef 'x' type does not match its declaration")]
-    fn var_ref_decl_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![Decl::FuncDecl(
-                "main".to_string(),
-                Visibility::Public,
-                Some(Type::Int32),
-                vec![],
-                false,
-                vec![
-                    Stmt::VarDecl("x".to_string(), Expr::ConstBool(true)),
-                    Stmt::Ret(Some(Expr::VarRef("x".to_string(), Type::Int32, false))),
-                ],
-            )],
-        }];
+    fn global_duplicate() {
+        let modules = main_with(
+            vec![
+                limit_const(),
+                Decl::Global(
+                    "limit".to_string(),
+                    Visibility::Private,
+                    vec![],
+                    Type::Int32,
+                    None,
+                ),
+            ],
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1004", " > limit".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Expression passed to not must be of type bool")]
-    fn not_expr_type_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![Decl::FuncDecl(
-                "main".to_string(),
-                Visibility::Public,
-                Some(Type::Int32),
-                vec![],
+    fn const_assign() {
+        let modules = main_with(
+            vec![limit_const()],
+            vec![
+                Stmt::Assign("limit".to_string(), Expr::ConstInt32(4)),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2005", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn var_decl_shadows_global() {
+        let modules = main_with(
+            vec![limit_const()],
+            vec![
+                Stmt::VarDecl("limit".to_string(), Expr::ConstInt32(4)),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E2002", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn global_ref_before_decl() {
+        let mut modules = main_with(
+            vec![],
+            vec![Stmt::Ret(Some(Expr::VarRef(
+                "limit".to_string(),
+                Type::Int32,
                 false,
-                vec![
-                    Stmt::VarDecl("x".to_string(), Expr::Not(Box::new(Expr::ConstInt32(3)))),
-                    Stmt::Ret(Some(Expr::ConstInt32(0))),
-                ],
-            )],
-        }];
+            )))],
+        );
+        modules[0].decls.push(limit_const());
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3035", " > main > stmt 0".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "FuncCall 'puts' parameter count does not match forward declaration")]
-    fn func_call_fewer_fixed_params() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "puts".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![("s".to_string(), Type::Str)],
-                    false,
+    fn cast_str_to_int64() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Cast(Type::Int64, Box::new(Expr::ConstStr("1".to_string()))),
                 ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![
-                        Stmt::VarDecl(
-                            "r".to_string(),
-                            Expr::FuncCall("puts".to_string(), Type::Int32, vec![]),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3014", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn cast_double_to_bool() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Cast(Type::Bool, Box::new(Expr::ConstDouble(1.0))),
                 ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
             ],
-        }];
+        );
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3014", " > main > stmt 0".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "FuncCall 'puts' parameter count does not match forward declaration")]
-    fn func_call_more_fixed_params() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "puts".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![("s".to_string(), Type::Str)],
-                    false,
+    fn cast_checks_expr() {
+        let modules = main_with(
+            vec![],
+            vec![Stmt::Ret(Some(Expr::Cast(
+                Type::Int32,
+                Box::new(Expr::VarRef("y".to_string(), Type::Int64, false)),
+            )))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3035", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn and_int_operand() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::And(
+                        Box::new(Expr::ConstBool(true)),
+                        Box::new(Expr::ConstInt32(1)),
+                    ),
                 ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![
-                        Stmt::VarDecl(
-                            "r".to_string(),
-                            Expr::FuncCall(
-                                "puts".to_string(),
-                                Type::Int32,
-                                vec![
-                                    Expr::ConstStr("hello world".to_string()),
-                                    Expr::ConstStr("err?".to_string()),
-                                ],
-                            ),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3005", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn or_int_operand() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Or(
+                        Box::new(Expr::ConstInt32(1)),
+                        Box::new(Expr::ConstBool(true)),
+                    ),
                 ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
             ],
-        }];
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3005", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn or_checks_operands() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Or(
+                        Box::new(Expr::ConstBool(false)),
+                        Box::new(Expr::VarRef("y".to_string(), Type::Bool, false)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3035", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn bitwise_double() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Bitwise(
+                        BitOp::Xor,
+                        Box::new(Expr::ConstDouble(1.0)),
+                        Box::new(Expr::ConstDouble(2.0)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3013", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn bitwise_bool() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Bitwise(
+                        BitOp::And,
+                        Box::new(Expr::ConstBool(true)),
+                        Box::new(Expr::ConstBool(false)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3013", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn bitwise_type_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Bitwise(
+                        BitOp::Shl,
+                        Box::new(Expr::ConstInt64(1)),
+                        Box::new(Expr::ConstInt32(2)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3012", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn neg_unsigned() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("x".to_string(), Expr::Neg(Box::new(Expr::ConstUInt32(1)))),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3027", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn rem_float32() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Arith(
+                        ArithOp::Rem,
+                        Box::new(Expr::ConstFloat32(1.5)),
+                        Box::new(Expr::ConstFloat32(0.5)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3007", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn unsigned_cmp_float32() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Cmp(
+                        Op::ULt,
+                        Box::new(Expr::ConstFloat32(1.5)),
+                        Box::new(Expr::ConstFloat32(0.5)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3018", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn arith_signedness_mismatch() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Arith(
+                        ArithOp::Add,
+                        Box::new(Expr::ConstInt8(1)),
+                        Box::new(Expr::ConstUInt8(1)),
+                    ),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3006", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn cast_float32_to_bool() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Cast(Type::Bool, Box::new(Expr::ConstFloat32(1.0))),
+                ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3014", " > main > stmt 0".to_string())]
+        );
+    }
+
+    fn counter_global(linkage: Vec<Linkage>) -> Decl {
+        Decl::Global(
+            "counter".to_string(),
+            Visibility::Private,
+            linkage,
+            Type::Int32,
+            None,
+        )
+    }
+
+    #[test]
+    fn const_thread_local() {
+        let modules = main_with(
+            vec![Decl::Const(
+                "limit".to_string(),
+                Visibility::Private,
+                vec![Linkage::Thread],
+                Expr::ConstInt32(3),
+            )],
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1005", " > limit".to_string())]
+        );
+    }
+
+    #[test]
+    fn global_thread_local_twice() {
+        let modules = main_with(
+            vec![counter_global(vec![Linkage::Thread, Linkage::Thread])],
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1017", " > counter".to_string())]
+        );
+    }
+
+    #[test]
+    fn global_two_sections() {
+        let modules = main_with(
+            vec![counter_global(vec![
+                Linkage::Section(".data.a".to_string(), None),
+                Linkage::Section(".data.b".to_string(), Some("aw".to_string())),
+            ])],
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1018", " > counter".to_string())]
+        );
+    }
+
+    #[test]
+    fn global_empty_section() {
+        let modules = main_with(
+            vec![counter_global(vec![Linkage::Section("".to_string(), None)])],
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1016", " > counter".to_string())]
+        );
+    }
+
+    fn fail_decl(r#type: Option<Type>, stmts: Vec<Stmt>) -> Decl {
+        Decl::FuncDecl(
+            "fail".to_string(),
+            Visibility::Private,
+            r#type,
+            vec![],
+            false,
+            true,
+            stmts,
+        )
+    }
+
+    #[test]
+    fn noreturn_with_type() {
+        let modules = main_with(
+            vec![fail_decl(Some(Type::Int32), vec![Stmt::Unreachable])],
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1019", " > fail".to_string())]
+        );
+    }
+
+    #[test]
+    fn noreturn_with_ret() {
+        let modules = main_with(
+            vec![fail_decl(
+                None,
+                vec![
+                    Stmt::Cond(vec![(Expr::ConstBool(true), vec![Stmt::Ret(None)])]),
+                    Stmt::Unreachable,
+                ],
+            )],
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1009", " > fail".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(
-        expected = "FuncCall 'puts' parameter 0 type does not match forward declaration"
-    )]
-    fn func_call_fixed_param_type_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
+    fn noreturn_reaches_end() {
+        let modules = main_with(
+            vec![
                 Decl::FwdDecl(
                     "puts".to_string(),
                     Visibility::Public,
                     Some(Type::Int32),
                     vec![("s".to_string(), Type::Str)],
                     false,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
                     false,
-                    vec![
-                        Stmt::VarDecl(
-                            "r".to_string(),
-                            Expr::FuncCall(
-                                "puts".to_string(),
-                                Type::Int32,
-                                vec![Expr::ConstInt32(1)],
-                            ),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
+                ),
+                fail_decl(
+                    None,
+                    vec![Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("failing".to_string())],
+                    )],
                 ),
             ],
-        }];
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1010", " > fail".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(
-        expected = "FuncCall 'frexp' parameter 1 type does not match forward declaration"
-    )]
-    fn func_call_by_ref_type_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
+    fn noreturn_fwd_decl_mismatch() {
+        let modules = main_with(
+            vec![
                 Decl::FwdDecl(
-                    "frexp".to_string(),
-                    Visibility::Public,
-                    Some(Type::Double),
-                    vec![
-                        ("x".to_string(), Type::Double),
-                        ("exp".to_string(), Type::Ptr(Some(Box::new(Type::Int32)))),
-                    ],
-                    false,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
+                    "fail".to_string(),
+                    Visibility::Private,
+                    None,
                     vec![],
                     false,
-                    vec![
-                        Stmt::VarDecl("bad_exp".to_string(), Expr::ConstBool(false)),
-                        Stmt::VarDecl(
-                            "r".to_string(),
-                            Expr::FuncCall(
-                                "frexp".to_string(),
-                                Type::Double,
-                                vec![
-                                    Expr::ConstDouble(2560.0),
-                                    Expr::VarRef("bad_exp".to_string(), Type::Bool, true),
-                                ],
-                            ),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
+                    false,
                 ),
+                fail_decl(None, vec![Stmt::Unreachable]),
             ],
-        }];
+            vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1006", " > fail".to_string())]
+        );
+    }
 
-        type_check(&modules).unwrap();
+    fn cond_ret(exprs: Vec<Expr>) -> Stmt {
+        Stmt::Cond(
+            exprs
+                .into_iter()
+                .map(|expr| (expr, vec![Stmt::Ret(Some(Expr::ConstInt32(0)))]))
+                .collect(),
+        )
     }
 
     #[test]
-    #[should_panic(
-        expected = "FuncCall 'not_ok' parameter 0 type does not match forward declaration"
-    )]
-    fn nested_func_call_type_mismatch() {
-        let modules = [Module {
-            name: "hello_world".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "puts".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![("s".to_string(), Type::Str)],
-                    false,
-                ),
-                Decl::FwdDecl(
-                    "not_ok".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![("s".to_string(), Type::Str)],
-                    false,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![Stmt::Ret(Some(Expr::FuncCall(
-                        "not_ok".to_string(),
-                        Type::Int32,
-                        vec![Expr::FuncCall(
-                            "puts".to_string(),
-                            Type::Int32,
-                            vec![Expr::ConstStr("hello world".to_string())],
-                        )],
-                    )))],
+    fn ret_in_every_cond_case() -> TestResult {
+        let modules = main_with(
+            vec![],
+            vec![cond_ret(vec![
+                Expr::ConstBool(false),
+                Expr::ConstBool(true),
+            ])],
+        );
+
+        assert!(type_check(&modules, Rules::default())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn ret_in_infinite_loop() -> TestResult {
+        let modules = main_with(
+            vec![],
+            vec![Stmt::While(
+                Expr::ConstBool(true),
+                vec![
+                    Stmt::While(Expr::ConstBool(true), vec![Stmt::Break]),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            )],
+        );
+
+        assert!(type_check(&modules, Rules::default())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_ret() {
+        let modules = main_with(vec![], vec![]);
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1011", " > main".to_string())]
+        );
+    }
+
+    #[test]
+    fn missing_ret_without_default_case() {
+        let modules = main_with(
+            vec![],
+            vec![cond_ret(vec![
+                Expr::ConstBool(false),
+                Expr::ConstBool(false),
+            ])],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1011", " > main".to_string())]
+        );
+    }
+
+    #[test]
+    fn missing_ret_in_cond_case() {
+        let modules = main_with(
+            vec![],
+            vec![Stmt::Cond(vec![
+                (
+                    Expr::ConstBool(false),
+                    vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
                 ),
+                (Expr::ConstBool(true), vec![]),
+            ])],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1011", " > main".to_string())]
+        );
+    }
+
+    #[test]
+    fn missing_ret_after_break() {
+        let modules = main_with(
+            vec![],
+            vec![Stmt::While(
+                Expr::ConstBool(true),
+                vec![Stmt::Cond(vec![(
+                    Expr::ConstBool(false),
+                    vec![Stmt::Break],
+                )])],
+            )],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1011", " > main".to_string())]
+        );
+    }
+
+    #[test]
+    fn unreachable_after_ret() -> TestResult {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+                Stmt::Ret(Some(Expr::ConstInt32(1))),
             ],
-        }];
+        );
 
-        type_check(&modules).unwrap();
+        let warnings = type_check(&modules, Rules::default())?;
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "W1001");
+        assert_eq!(warnings[0].path, ["", "main", "stmt 1"]);
+        Ok(())
     }
 
     #[test]
-    #[should_panic(
-        expected = "FuncCall 'printf' parameter count does not match forward declaration"
-    )]
-    fn func_call_variadic_params_just_one_too_few() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "printf".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![("fmt".to_string(), Type::Str)],
-                    true,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![
-                        Stmt::VarDecl(
-                            "r1".to_string(),
-                            Expr::FuncCall("printf".to_string(), Type::Int32, vec![]),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
-                ),
+    fn unreachable_after_cond() -> TestResult {
+        let modules = main_with(
+            vec![],
+            vec![
+                cond_ret(vec![Expr::ConstBool(true)]),
+                Stmt::Ret(Some(Expr::ConstInt32(1))),
             ],
-        }];
+        );
 
-        type_check(&modules).unwrap();
+        let warnings = type_check(&modules, Rules::default())?;
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "W1001");
+        assert_eq!(warnings[0].path, ["", "main", "stmt 1"]);
+        Ok(())
     }
 
     #[test]
-    #[should_panic(expected = "Func 'printf' requires at least one argument since it is variadic")]
-    fn fwd_decl_variadic_no_params() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "printf".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    true,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![
-                        Stmt::VarDecl(
-                            "r1".to_string(),
-                            Expr::FuncCall("printf".to_string(), Type::Int32, vec![]),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
+    fn unreachable_after_continue() -> TestResult {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::While(Expr::ConstBool(false), vec![Stmt::Continue, Stmt::Break]),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        let warnings = type_check(&modules, Rules::default())?;
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "W1001");
+        assert_eq!(warnings[0].path, ["", "main", "stmt 0", "stmt 1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn unreachable_in_cond_at_loc() -> TestResult {
+        let loc = Loc::Pos("main.bpmn".to_string(), 7, Some(2));
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::Cond(vec![
+                    (Expr::ConstBool(false), vec![]),
+                    (
+                        Expr::ConstBool(true),
+                        vec![
+                            Stmt::Ret(Some(Expr::ConstInt32(0))),
+                            Stmt::Loc(loc.clone()),
+                            Stmt::Ret(Some(Expr::ConstInt32(1))),
+                            Stmt::Ret(Some(Expr::ConstInt32(2))),
+                        ],
+                    ),
+                ]),
+                Stmt::Ret(Some(Expr::ConstInt32(3))),
+            ],
+        );
+
+        let warnings = type_check(&modules, Rules::default())?;
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "W1001");
+        assert_eq!(warnings[0].path, ["", "main", "stmt 0", "case 1", "stmt 1"]);
+        assert_eq!(warnings[0].loc.as_deref(), Some(&loc));
+        Ok(())
+    }
+
+    fn puts_decl() -> Decl {
+        Decl::FwdDecl(
+            "puts".to_string(),
+            Visibility::Public,
+            Some(Type::Int32),
+            vec![("s".to_string(), Type::Str)],
+            false,
+            false,
+        )
+    }
+
+    fn cmp_main(lhs: Expr, rhs: Expr) -> [Module; 1] {
+        main_with(
+            vec![puts_decl()],
+            vec![
+                Stmt::VarDecl(
+                    "c".to_string(),
+                    Expr::Cmp(Op::Eq, Box::new(lhs), Box::new(rhs)),
                 ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
             ],
-        }];
+        )
+    }
 
-        type_check(&modules).unwrap();
+    #[test]
+    fn cmp_undeclared_var_ref() {
+        let modules = cmp_main(
+            Expr::VarRef("x".to_string(), Type::Int32, false),
+            Expr::ConstInt32(1),
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3035", " > main > stmt 0".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Func 'main' requires at least one argument since it is variadic")]
-    fn func_decl_variadic_no_params() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "printf".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
+    fn cmp_func_call_param_type() {
+        let modules = cmp_main(
+            Expr::ConstInt32(1),
+            Expr::FuncCall("puts".to_string(), Type::Int32, vec![Expr::ConstInt32(1)]),
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3002", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn cmp_func_call_no_fwd_decl() {
+        let modules = cmp_main(
+            Expr::FuncCall(
+                "strlen".to_string(),
+                Type::Int32,
+                vec![Expr::ConstStr("x".to_string())],
+            ),
+            Expr::ConstInt32(1),
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3003", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn not_undeclared_var_ref() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl(
+                    "c".to_string(),
+                    Expr::Not(Box::new(Expr::VarRef("b".to_string(), Type::Bool, false))),
                 ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    true,
-                    vec![
-                        Stmt::VarDecl(
-                            "r1".to_string(),
-                            Expr::FuncCall("printf".to_string(), Type::Int32, vec![]),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3035", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_stmt_no_fwd_decl() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::FuncCall(
+                    "puts".to_string(),
+                    vec![Expr::ConstStr("hello world".to_string())],
                 ),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3003", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_stmt_param_count() {
+        let modules = main_with(
+            vec![puts_decl()],
+            vec![
+                Stmt::FuncCall("puts".to_string(), vec![]),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+            ],
+        );
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3001", " > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn func_call_stmt_param_type() {
+        let modules = main_with(
+            vec![puts_decl()],
+            vec![
+                Stmt::FuncCall("puts".to_string(), vec![Expr::ConstInt64(1)]),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
             ],
-        }];
+        );
 
-        type_check(&modules).unwrap();
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3002", " > main > stmt 0".to_string())]
+        );
     }
 
     #[test]
-    #[should_panic(
-        expected = "FuncCall 'printf' parameter 0 type does not match forward declaration"
-    )]
-    fn func_call_variadic_params_just_one_type_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "printf".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![("fmt".to_string(), Type::Str)],
-                    true,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![
-                        Stmt::VarDecl(
-                            "r1".to_string(),
-                            Expr::FuncCall(
-                                "printf".to_string(),
-                                Type::Int32,
-                                vec![Expr::ConstInt32(1)],
-                            ),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
-                ),
+    fn diagnostic_code_and_path() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::VarDecl("x".to_string(), Expr::ConstInt32(1)),
+                Stmt::Cond(vec![(
+                    Expr::ConstBool(true),
+                    vec![Stmt::Assign("x".to_string(), Expr::ConstInt32(2))],
+                )]),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
             ],
-        }];
+        );
 
-        type_check(&modules).unwrap();
+        let diagnostics = type_check(&modules, Rules::default()).unwrap_err();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "E2005");
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            diagnostics[0].path,
+            ["", "main", "stmt 1", "case 0", "stmt 0"]
+        );
     }
 
     #[test]
-    #[should_panic(
-        expected = "FuncCall 'printnf' parameter count does not match forward declaration"
-    )]
-    fn func_call_variadic_params_with_many_too_few() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "printnf".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![
-                        ("fmt".to_string(), Type::Str),
-                        ("n".to_string(), Type::Int32),
-                    ],
-                    true,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![
-                        Stmt::VarDecl(
-                            "r1".to_string(),
-                            Expr::FuncCall(
-                                "printnf".to_string(),
-                                Type::Int32,
-                                vec![Expr::ConstStr("hello world".to_string())],
-                            ),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
-                ),
+    fn diagnostic_notes() {
+        let modules = main_with(
+            vec![puts_decl()],
+            vec![
+                Stmt::FuncCall("puts".to_string(), vec![Expr::ConstInt64(1)]),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
             ],
-        }];
+        );
 
-        type_check(&modules).unwrap();
+        let diagnostics = type_check(&modules, Rules::default()).unwrap_err();
+
+        assert_eq!(diagnostics[0].code, "E3002");
+        assert_eq!(diagnostics[0].notes, ["expected Str, found Int64"]);
     }
 
     #[test]
-    #[should_panic(
-        expected = "FuncCall 'printnf' parameter 1 type does not match forward declaration"
-    )]
-    fn func_call_variadic_params_with_many_type_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
-            decls: vec![
-                Decl::FwdDecl(
-                    "printnf".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![
-                        ("fmt".to_string(), Type::Str),
-                        ("n".to_string(), Type::Int32),
-                    ],
-                    true,
-                ),
-                Decl::FuncDecl(
-                    "main".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![],
-                    false,
-                    vec![
-                        Stmt::VarDecl(
-                            "r1".to_string(),
-                            Expr::FuncCall(
-                                "printnf".to_string(),
-                                Type::Int32,
-                                vec![
-                                    Expr::ConstStr("hello world".to_string()),
-                                    Expr::ConstStr("hello world".to_string()),
-                                ],
-                            ),
-                        ),
-                        Stmt::Ret(Some(Expr::ConstInt32(0))),
-                    ],
+    fn multiple_errors() {
+        let modules = main_with(
+            vec![Decl::FuncDecl(
+                "f".to_string(),
+                Visibility::Private,
+                None,
+                vec![],
+                false,
+                false,
+                vec![Stmt::Break],
+            )],
+            vec![
+                Stmt::VarDecl(
+                    "x".to_string(),
+                    Expr::Not(Box::new(Expr::VarRef("y".to_string(), Type::Bool, false))),
                 ),
+                Stmt::Assign("x".to_string(), Expr::ConstBool(true)),
+                Stmt::Cond(vec![(
+                    Expr::VarRef("x".to_string(), Type::Bool, false),
+                    vec![Stmt::Continue, Stmt::Ret(Some(Expr::ConstBool(false)))],
+                )]),
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
             ],
-        }];
+        );
 
-        type_check(&modules).unwrap();
+        let diagnostics = type_check(&modules, Rules::default()).unwrap_err();
+        let errors = diagnostics
+            .iter()
+            .filter(|d| d.is_error())
+            .map(|d| (d.code, d.path.join(" > ")))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            [
+                ("E2007", " > f > stmt 0".to_string()),
+                ("E3035", " > main > stmt 0".to_string()),
+                ("E2005", " > main > stmt 1".to_string()),
+                ("E2009", " > main > stmt 2 > case 0 > stmt 0".to_string()),
+                ("E2001", " > main > stmt 2 > case 0 > stmt 1".to_string()),
+            ]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Cond case expressions must be of type bool")]
-    fn func_cond_case_not_bool() {
+    fn errors_and_warnings() {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+                Stmt::Assign("x".to_string(), Expr::ConstInt32(1)),
+            ],
+        );
+
+        let diagnostics = type_check(&modules, Rules::default()).unwrap_err();
+        let codes = diagnostics.iter().map(|d| d.code).collect::<Vec<_>>();
+
+        assert_eq!(codes, ["E2006", "W1001"]);
+    }
+
+    #[test]
+    fn diagnostic_locs() {
+        let pos = |line| Loc::Pos("main.bpmn".to_string(), line, None);
         let modules = [Module {
             name: "".to_string(),
             decls: vec![
-                Decl::FwdDecl(
-                    "printnf".to_string(),
-                    Visibility::Public,
-                    Some(Type::Int32),
-                    vec![
-                        ("fmt".to_string(), Type::Str),
-                        ("n".to_string(), Type::Int32),
-                    ],
-                    true,
-                ),
+                Decl::Loc(pos(1)),
+                Decl::StructDecl("empty".to_string(), vec![]),
+                Decl::Loc(pos(2)),
                 Decl::FuncDecl(
                     "main".to_string(),
                     Visibility::Public,
                     Some(Type::Int32),
                     vec![],
                     false,
+                    false,
                     vec![
+                        Stmt::Break,
+                        Stmt::Loc(pos(3)),
                         Stmt::Cond(vec![(
-                            Expr::ConstInt32(3),
-                            vec![Stmt::VarDecl(
-                                "r1".to_string(),
-                                Expr::FuncCall(
-                                    "printnf".to_string(),
-                                    Type::Int32,
-                                    vec![
-                                        Expr::ConstStr("hello world".to_string()),
-                                        Expr::ConstStr("hello world".to_string()),
-                                    ],
-                                ),
-                            )],
+                            Expr::ConstBool(true),
+                            vec![
+                                Stmt::Continue,
+                                Stmt::Loc(Loc::Element("Task_1".to_string())),
+                                Stmt::Break,
+                            ],
                         )]),
                         Stmt::Ret(Some(Expr::ConstInt32(0))),
+                        Stmt::Loc(pos(4)),
                     ],
                 ),
             ],
         }];
 
-        type_check(&modules).unwrap();
+        let diagnostics = type_check(&modules, Rules::default()).unwrap_err();
+        let locs = diagnostics
+            .iter()
+            .map(|d| (d.code, d.loc.as_ref().map(|l| l.to_string())))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            locs,
+            [
+                ("E1013", Some("main.bpmn:1".to_string())),
+                ("E2007", Some("main.bpmn:2".to_string())),
+                ("E2009", Some("main.bpmn:3".to_string())),
+                ("E2007", Some("element 'Task_1'".to_string())),
+                ("W1001", Some("main.bpmn:3".to_string())),
+            ]
+        );
+        assert_eq!(
+            diagnostics[3].path,
+            ["", "main", "stmt 1", "case 0", "stmt 1"]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Comparison 'eq' has different left hand and right hand side types")]
-    fn cmp_eq_type_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
+    fn loc_after_ret() -> TestResult {
+        let modules = main_with(
+            vec![],
+            vec![
+                Stmt::Ret(Some(Expr::ConstInt32(0))),
+                Stmt::Loc(Loc::Element("End_1".to_string())),
+            ],
+        );
+
+        assert!(type_check(&modules, Rules::default())?.is_empty());
+        Ok(())
+    }
+
+    fn sayer_module(visibility: Visibility) -> Module {
+        Module {
+            name: "sayer".to_string(),
             decls: vec![Decl::FuncDecl(
-                "main".to_string(),
-                Visibility::Public,
+                "say_hello_world".to_string(),
+                visibility,
                 Some(Type::Int32),
                 vec![],
                 false,
-                vec![
-                    Stmt::VarDecl(
-                        "bad_eq".to_string(),
-                        Expr::Cmp(
-                            Op::Eq,
-                            Box::new(Expr::ConstBool(true)),
-                            Box::new(Expr::ConstInt32(11)),
-                        ),
-                    ),
-                    Stmt::Ret(Some(Expr::ConstInt32(0))),
-                ],
+                false,
+                vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
             )],
-        }];
+        }
+    }
+
+    fn calling_module(decls: Vec<Decl>) -> Module {
+        let main = Decl::FuncDecl(
+            "main".to_string(),
+            Visibility::Public,
+            Some(Type::Int32),
+            vec![],
+            false,
+            false,
+            vec![Stmt::Ret(Some(Expr::FuncCall(
+                "say_hello_world".to_string(),
+                Type::Int32,
+                vec![],
+            )))],
+        );
 
-        type_check(&modules).unwrap();
+        Module {
+            name: "caller".to_string(),
+            decls: decls.into_iter().chain([main]).collect(),
+        }
+    }
+
+    fn say_hello_world_fwd_decl(r#type: Type) -> Decl {
+        Decl::FwdDecl(
+            "say_hello_world".to_string(),
+            Visibility::Public,
+            Some(r#type),
+            vec![],
+            false,
+            false,
+        )
     }
 
     #[test]
-    #[should_panic(expected = "Comparison 'ne' has different left hand and right hand side types")]
-    fn cmp_ne_type_mismatch() {
-        let modules = [Module {
-            name: "".to_string(),
+    fn fwd_decl_matches_other_module() -> TestResult {
+        let modules = [
+            calling_module(vec![say_hello_world_fwd_decl(Type::Int32)]),
+            sayer_module(Visibility::Public),
+        ];
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn fwd_decl_mismatches_other_module() {
+        let modules = [
+            calling_module(vec![say_hello_world_fwd_decl(Type::Int64)]),
+            sayer_module(Visibility::Public),
+        ];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [
+                ("E1023", "caller > say_hello_world".to_string()),
+                ("E3003", "caller > main > stmt 0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn call_private_func_of_other_module() {
+        let modules = [calling_module(vec![]), sayer_module(Visibility::Private)];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3038", "caller > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn fwd_decl_shares_name_with_private_func_of_other_module() -> TestResult {
+        let modules = [
+            calling_module(vec![say_hello_world_fwd_decl(Type::Int32)]),
+            sayer_module(Visibility::Private),
+        ];
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn import() -> TestResult {
+        let modules = [
+            calling_module(vec![Decl::Import("sayer".to_string())]),
+            sayer_module(Visibility::Public),
+        ];
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn import_skips_private_funcs() {
+        let modules = [
+            calling_module(vec![Decl::Import("sayer".to_string())]),
+            sayer_module(Visibility::Private),
+        ];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E3038", "caller > main > stmt 0".to_string())]
+        );
+    }
+
+    #[test]
+    fn import_local_decl_mismatch() {
+        let local = sayer_module(Visibility::Private).decls.remove(0);
+        let modules = [
+            calling_module(vec![local, Decl::Import("sayer".to_string())]),
+            sayer_module(Visibility::Public),
+        ];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [("E1027", "caller > sayer".to_string())]
+        );
+    }
+
+    #[test]
+    fn import_unknown_module() {
+        let modules = [calling_module(vec![Decl::Import("greeter".to_string())])];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [
+                ("E1025", "caller > greeter".to_string()),
+                ("E3003", "caller > main > stmt 0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_self() {
+        let modules = [
+            calling_module(vec![Decl::Import("caller".to_string())]),
+            sayer_module(Visibility::Public),
+        ];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [
+                ("E1026", "caller > caller".to_string()),
+                ("E3003", "caller > main > stmt 0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_public_func() {
+        let mut sayer = sayer_module(Visibility::Public);
+        sayer.name = "other_sayer".to_string();
+
+        let modules = [sayer_module(Visibility::Public), sayer];
+
+        assert_eq!(
+            codes_and_paths(&modules),
+            [
+                ("E1024", "sayer > say_hello_world".to_string()),
+                ("E1024", "other_sayer > say_hello_world".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_private_func() -> TestResult {
+        let mut sayer = sayer_module(Visibility::Private);
+        sayer.name = "other_sayer".to_string();
+
+        let modules = [sayer_module(Visibility::Public), sayer];
+
+        type_check(&modules, Rules::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn entry_point() -> Res<()> {
+        check_entry_point(&mtc::hello_world())?;
+        check_entry_point(&mtc::hello_world2())?;
+
+        Ok(())
+    }
+
+    fn main_module(visibility: Visibility, r#type: Type, args: Vec<FuncArg>) -> [Module; 1] {
+        [Module {
+            name: "app".to_string(),
             decls: vec![Decl::FuncDecl(
                 "main".to_string(),
-                Visibility::Public,
-                Some(Type::Int32),
-                vec![],
+                visibility,
+                Some(r#type),
+                args,
                 false,
-                vec![
-                    Stmt::VarDecl(
-                        "bad_ne".to_string(),
-                        Expr::Cmp(
-                            Op::Ne,
-                            Box::new(Expr::ConstBool(true)),
-                            Box::new(Expr::ConstInt32(11)),
-                        ),
-                    ),
-                    Stmt::Ret(Some(Expr::ConstInt32(0))),
-                ],
+                false,
+                vec![Stmt::Ret(Some(Expr::ConstInt32(0)))],
             )],
-        }];
+        }]
+    }
+
+    fn argc_argv(argv: Type) -> Vec<FuncArg> {
+        vec![
+            ("argc".to_string(), Type::Int32),
+            ("argv".to_string(), argv),
+        ]
+    }
+
+    #[test]
+    fn entry_point_argc_argv() -> Res<()> {
+        let argv = Type::Ptr(Some(Box::new(Type::Str)));
+        check_entry_point(&main_module(
+            Visibility::Public,
+            Type::Int32,
+            argc_argv(argv),
+        ))?;
+        check_entry_point(&main_module(
+            Visibility::Public,
+            Type::Int32,
+            argc_argv(Type::Ptr(None)),
+        ))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn entry_point_missing() {
+        let modules = [sayer_module(Visibility::Public)];
+        let err = check_entry_point(&modules).unwrap_err();
+
+        assert_eq!(err.code, "E1029");
+        assert!(err.notes.is_empty());
+    }
 
-        type_check(&modules).unwrap();
+    #[test]
+    fn entry_point_private() {
+        let modules = main_module(Visibility::Private, Type::Int32, vec![]);
+        let err = check_entry_point(&modules).unwrap_err();
+
+        assert_eq!(err.code, "E1029");
+        assert_eq!(err.notes, ["'main' is private in module 'app'"]);
+    }
+
+    #[test]
+    fn entry_point_signature() {
+        for modules in [
+            main_module(Visibility::Public, Type::Int64, vec![]),
+            main_module(
+                Visibility::Public,
+                Type::Int32,
+                vec![("argc".to_string(), Type::Int32)],
+            ),
+            main_module(Visibility::Public, Type::Int32, argc_argv(Type::Str)),
+        ] {
+            let err = check_entry_point(&modules).unwrap_err();
+
+            assert_eq!(err.code, "E1030");
+            assert_eq!(err.path, ["app", "main"]);
+        }
     }
 }
@@ -1,17 +1,58 @@
 TESTS := \
 	hello_world \
 	hello_world2 \
+	hello_world3 \
 	fabs \
 	frexp \
 	cmp \
 	cond \
-	not
+	not \
+	add \
+	sub \
+	mul \
+	div \
+	rem \
+	neg \
+	ord \
+	loops \
+	assign \
+	structs \
+	arrays \
+	globals \
+	casts \
+	logic \
+	bitwise \
+	numbers \
+	linkage \
+	noreturn \
+	loc \
+	shadowing \
+	strings
 
 $(TESTS):
 	make TEST_CASE=$@ test-compile test-run && \
 	echo ""
 
-integration-tests: $(TESTS)
+# The executable links against the shared lib, which it finds through LD_LIBRARY_PATH at run time
+shared_lib:
+	$(IN_DEV) $(MLC) \
+		--json-file $(TEST_CASES_DIR)/json/shared_lib.json \
+		--build-dir $(BUILD_DIR)/shared_lib \
+		--ninja $(NINJA) \
+		--kind shared \
+		-o libshared_lib.so && \
+	$(IN_DEV) $(MLC) \
+		--json-file $(TEST_CASES_DIR)/json/shared_lib_main.json \
+		--build-dir $(BUILD_DIR)/shared_lib_main \
+		--ninja $(NINJA) \
+		-lshared_lib \
+		-L../shared_lib \
+		-o shared_lib_main && \
+	$(IN_DEV) env LD_LIBRARY_PATH=$(BUILD_DIR)/shared_lib \
+		$(BUILD_DIR)/shared_lib_main/shared_lib_main && \
+	echo ""
+
+integration-tests: $(TESTS) shared_lib
 	@/bin/true
 
-.PHONY: integration-tests $(TESTS)
+.PHONY: integration-tests $(TESTS) shared_lib
@@ -5,7 +5,7 @@ use serde_json::Value;
 #[serde(rename_all = "lowercase")]
 pub struct Module {
     pub name: String,
-    pub decls: Vec<Decl>,
+    pub decls: Vec<Located<Decl>>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -16,7 +16,17 @@ pub enum JSONLang {
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
+#[allow(clippy::enum_variant_names)]
 pub enum Decl {
+    Const {
+        name: String,
+        visibility: Visibility,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        linkage: Vec<Linkage>,
+
+        value: Expr,
+    },
     FwdDecl {
         name: String,
         visibility: Visibility,
@@ -28,6 +38,9 @@ pub enum Decl {
 
         #[serde(skip_serializing_if = "Option::is_none")]
         variadic: Option<bool>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        noreturn: Option<bool>,
     },
     FuncDecl {
         name: String,
@@ -41,7 +54,29 @@ pub enum Decl {
         #[serde(skip_serializing_if = "Option::is_none")]
         variadic: Option<bool>,
 
-        stmts: Vec<Stmt>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        noreturn: Option<bool>,
+
+        stmts: Vec<Located<Stmt>>,
+    },
+    Global {
+        name: String,
+        visibility: Visibility,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        linkage: Vec<Linkage>,
+
+        r#type: Type,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        value: Option<Expr>,
+    },
+    Import {
+        module: String,
+    },
+    StructDecl {
+        name: String,
+        fields: Vec<StructField>,
     },
 }
 
@@ -52,25 +87,137 @@ pub struct FuncArg {
     pub r#type: Type,
 }
 
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Linkage {
+    Section {
+        name: String,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        flags: Option<String>,
+    },
+    Thread,
+}
+
+// Any decl or stmt can carry an optional "loc" next to its own key
+#[derive(Deserialize, Serialize)]
+pub struct Located<T> {
+    #[serde(flatten)]
+    pub node: T,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc: Option<Loc>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Loc {
+    Pos {
+        file: String,
+        line: u32,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        col: Option<u32>,
+    },
+    Element {
+        id: String,
+    },
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub struct StructField {
+    pub name: String,
+    pub r#type: Type,
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Stmt {
-    Cond { cases: Vec<Case> },
-    FuncCall { name: String, args: Vec<Expr> },
-    Ret { value: Option<Expr> },
-    VarDecl { name: String, value: Expr },
+    Assign {
+        name: String,
+        value: Expr,
+    },
+    Break {},
+    Cond {
+        cases: Vec<Case>,
+    },
+    Continue {},
+    FuncCall {
+        name: String,
+        args: Vec<Expr>,
+    },
+    IndexAssign {
+        name: String,
+        index: Expr,
+        value: Expr,
+    },
+    Ret {
+        value: Option<Expr>,
+    },
+    Store {
+        ptr: Expr,
+        value: Expr,
+    },
+    Unreachable {},
+    VarDecl {
+        name: String,
+        value: Expr,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mutable: Option<bool>,
+    },
+    While {
+        expr: Expr,
+        stmts: Vec<Located<Stmt>>,
+    },
 }
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub struct Case {
     pub expr: Expr,
-    pub stmts: Vec<Stmt>,
+    pub stmts: Vec<Located<Stmt>>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub struct FieldInit {
+    pub name: String,
+    pub value: Expr,
 }
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Expr {
+    Add {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    And {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    ArrayLit {
+        r#type: Type,
+        elems: Vec<Expr>,
+    },
+    BitAnd {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    BitOr {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    BitXor {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Cast {
+        r#type: Type,
+        expr: Box<Expr>,
+    },
     Eq {
         lhs: Box<Expr>,
         rhs: Box<Expr>,
@@ -79,18 +226,107 @@ pub enum Expr {
         value: Value,
         r#type: Type,
     },
+    Div {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Field {
+        expr: Box<Expr>,
+        name: String,
+        r#type: Type,
+    },
     FuncCall {
         name: String,
         r#type: Type,
         args: Vec<Expr>,
     },
+    Ge {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Gt {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Index {
+        expr: Box<Expr>,
+        index: Box<Expr>,
+        r#type: Type,
+    },
+    Le {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Load {
+        ptr: Box<Expr>,
+        r#type: Type,
+    },
+    Lt {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Mul {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
     Ne {
         lhs: Box<Expr>,
         rhs: Box<Expr>,
     },
+    Neg {
+        expr: Box<Expr>,
+    },
     Not {
         expr: Box<Expr>,
     },
+    Or {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    PtrAdd {
+        ptr: Box<Expr>,
+        index: Box<Expr>,
+    },
+    Rem {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Sar {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Shl {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Shr {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    StructLit {
+        r#type: Type,
+        fields: Vec<FieldInit>,
+    },
+    Sub {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    UGe {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    UGt {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    ULe {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    ULt {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
     VarRef {
         name: String,
         r#type: Type,
@@ -110,11 +346,20 @@ pub enum Visibility {
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Type {
+    Array { of: Box<Type>, len: usize },
     Bool,
     Double,
+    Float32,
+    Int8,
+    Int16,
     Int32,
     Int64,
     Ptr { to: Box<Type> },
     Str,
+    Struct { name: String },
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
     VoidPtr,
 }
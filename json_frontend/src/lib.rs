@@ -9,6 +9,7 @@ mod raise;
 use lower::lower;
 use raise::raise;
 
+use compiler::Diagnostic;
 use midlang as m;
 
 pub struct Frontend<'a> {
@@ -35,11 +36,21 @@ impl compiler::Frontend for Frontend<'_> {
 
 impl Frontend<'_> {
     fn lower_from_file(path: &Path) -> compiler::FrontendLowerResult {
-        let file = File::open(path)?;
+        let file = File::open(path).map_err(|err| {
+            Diagnostic::error(
+                "E0101",
+                format!("Cannot open '{}': {}", path.display(), err),
+            )
+        })?;
         let reader = BufReader::new(file);
-        let json_lang = serde_json::from_reader(reader)?;
+        let json_lang = serde_json::from_reader(reader).map_err(|err| {
+            Diagnostic::error(
+                "E0102",
+                format!("Invalid JSON in '{}': {}", path.display(), err),
+            )
+        })?;
 
-        lower(&json_lang)
+        Ok(lower(&json_lang)?)
     }
 
     fn raise_to_file(modules: &[m::Module], path: &Path) -> compiler::FrontendRaiseResult {
@@ -92,6 +103,11 @@ mod tests {
         test!(hello_world2)
     }
 
+    #[test]
+    fn hello_world3() -> TestResult {
+        test!(hello_world3)
+    }
+
     #[test]
     fn cond() -> TestResult {
         test!(cond)
@@ -116,4 +132,160 @@ mod tests {
     fn not() -> TestResult {
         test!(not)
     }
+
+    #[test]
+    fn add() -> TestResult {
+        test!(add)
+    }
+
+    #[test]
+    fn sub() -> TestResult {
+        test!(sub)
+    }
+
+    #[test]
+    fn mul() -> TestResult {
+        test!(mul)
+    }
+
+    #[test]
+    fn div() -> TestResult {
+        test!(div)
+    }
+
+    #[test]
+    fn rem() -> TestResult {
+        test!(rem)
+    }
+
+    #[test]
+    fn neg() -> TestResult {
+        test!(neg)
+    }
+
+    #[test]
+    fn ord() -> TestResult {
+        test!(ord)
+    }
+
+    #[test]
+    fn loops() -> TestResult {
+        test!(loops)
+    }
+
+    #[test]
+    fn assign() -> TestResult {
+        test!(assign)
+    }
+
+    #[test]
+    fn structs() -> TestResult {
+        test!(structs)
+    }
+
+    #[test]
+    fn arrays() -> TestResult {
+        test!(arrays)
+    }
+
+    #[test]
+    fn strings() -> TestResult {
+        test!(strings)
+    }
+
+    #[test]
+    fn globals() -> TestResult {
+        test!(globals)
+    }
+
+    #[test]
+    fn casts() -> TestResult {
+        test!(casts)
+    }
+
+    #[test]
+    fn logic() -> TestResult {
+        test!(logic)
+    }
+
+    #[test]
+    fn bitwise() -> TestResult {
+        test!(bitwise)
+    }
+
+    #[test]
+    fn numbers() -> TestResult {
+        test!(numbers)
+    }
+
+    #[test]
+    fn linkage() -> TestResult {
+        test!(linkage)
+    }
+
+    #[test]
+    fn noreturn() -> TestResult {
+        test!(noreturn)
+    }
+
+    #[test]
+    fn loc() -> TestResult {
+        test!(loc)
+    }
+
+    #[test]
+    fn shadowing() -> TestResult {
+        test!(shadowing)
+    }
+
+    #[test]
+    fn shared_lib() -> TestResult {
+        test!(shared_lib)
+    }
+
+    #[test]
+    fn shared_lib_main() -> TestResult {
+        test!(shared_lib_main)
+    }
+
+    #[test]
+    fn stray_locs() -> TestResult {
+        let loc = |id: &str| m::Loc::Element(id.to_string());
+        let modules = [m::Module {
+            name: "stray_locs".to_string(),
+            decls: vec![
+                m::Decl::Loc(loc("a")),
+                m::Decl::Loc(loc("b")),
+                m::Decl::FuncDecl(
+                    "main".to_string(),
+                    m::Visibility::Public,
+                    None,
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        m::Stmt::Loc(loc("c")),
+                        m::Stmt::Ret(None),
+                        m::Stmt::Loc(loc("d")),
+                    ],
+                ),
+                m::Decl::Loc(loc("e")),
+            ],
+        }];
+
+        let lowered = lower(&raise(&modules)?)?;
+        let [m::Decl::Loc(decl_loc), m::Decl::FuncDecl(_, _, _, _, _, _, stmts)] =
+            &lowered[0].decls[..]
+        else {
+            panic!("Expected the func with only its own Loc");
+        };
+        let [m::Stmt::Loc(stmt_loc), m::Stmt::Ret(None)] = &stmts[..] else {
+            panic!("Expected the ret with only its own Loc");
+        };
+
+        assert_eq!(*decl_loc, loc("b"));
+        assert_eq!(*stmt_loc, loc("c"));
+
+        Ok(())
+    }
 }
@@ -1,12 +1,11 @@
-use std::error::Error;
-
 use serde_json::Value;
 
+use compiler::Diagnostic;
 use midlang as m;
 
 use crate::json_lang::*;
 
-type Res<T> = Result<T, Box<dyn Error>>;
+type Res<T> = Result<T, Diagnostic>;
 
 pub fn lower(json_lang: &JSONLang) -> Res<Vec<m::Module>> {
     Ok(match json_lang {
@@ -16,7 +15,7 @@ pub fn lower(json_lang: &JSONLang) -> Res<Vec<m::Module>> {
             for module in modules {
                 lowered.push(m::Module {
                     name: module.name.to_string(),
-                    decls: lower_decls(&module.decls)?,
+                    decls: lower_decls(&module.decls).map_err(|err| err.within(&module.name))?,
                 });
             }
 
@@ -25,24 +24,59 @@ pub fn lower(json_lang: &JSONLang) -> Res<Vec<m::Module>> {
     })
 }
 
-fn lower_decls(decls: &[Decl]) -> Res<Vec<m::Decl>> {
-    decls.iter().map(lower_decl).collect()
+// Locs become a marker ahead of the node they were attached to
+fn lower_decls(decls: &[Located<Decl>]) -> Res<Vec<m::Decl>> {
+    let mut lowered = Vec::<m::Decl>::with_capacity(decls.len());
+
+    for Located { node, loc } in decls {
+        let loc = loc.as_ref().map(lower_loc);
+        let decl = lower_decl(node).map_err(|err| err.within(decl_name(node)).at(loc.as_ref()))?;
+
+        lowered.extend(loc.map(m::Decl::Loc));
+        lowered.push(decl);
+    }
+
+    Ok(lowered)
+}
+
+fn decl_name(decl: &Decl) -> &str {
+    match decl {
+        Decl::Const { name, .. }
+        | Decl::FwdDecl { name, .. }
+        | Decl::FuncDecl { name, .. }
+        | Decl::Global { name, .. }
+        | Decl::StructDecl { name, .. } => name,
+        Decl::Import { module } => module,
+    }
 }
 
 fn lower_decl(decl: &Decl) -> Res<m::Decl> {
     Ok(match decl {
+        Decl::Const {
+            name,
+            visibility,
+            linkage,
+            value,
+        } => m::Decl::Const(
+            name.to_string(),
+            lower_visibility(visibility),
+            lower_linkage(linkage),
+            lower_expr(value)?,
+        ),
         Decl::FwdDecl {
             name,
             visibility,
             r#type,
             args,
             variadic,
+            noreturn,
         } => m::Decl::FwdDecl(
             name.to_string(),
             lower_visibility(visibility),
             lower_opt_type(r#type),
             lower_args(args),
             variadic.unwrap_or(false),
+            noreturn.unwrap_or(false),
         ),
         Decl::FuncDecl {
             name,
@@ -50,6 +84,7 @@ fn lower_decl(decl: &Decl) -> Res<m::Decl> {
             r#type,
             args,
             variadic,
+            noreturn,
             stmts,
         } => m::Decl::FuncDecl(
             name.to_string(),
@@ -57,11 +92,52 @@ fn lower_decl(decl: &Decl) -> Res<m::Decl> {
             lower_opt_type(r#type),
             lower_args(args),
             variadic.unwrap_or(false),
+            noreturn.unwrap_or(false),
             lower_stmts(stmts)?,
         ),
+        Decl::Global {
+            name,
+            visibility,
+            linkage,
+            r#type,
+            value,
+        } => m::Decl::Global(
+            name.to_string(),
+            lower_visibility(visibility),
+            lower_linkage(linkage),
+            lower_type(r#type),
+            value.as_ref().map(lower_expr).transpose()?,
+        ),
+        Decl::Import { module } => m::Decl::Import(module.to_string()),
+        Decl::StructDecl { name, fields } => m::Decl::StructDecl(
+            name.to_string(),
+            fields
+                .iter()
+                .map(|f| (f.name.to_string(), lower_type(&f.r#type)))
+                .collect(),
+        ),
     })
 }
 
+fn lower_linkage(linkage: &[Linkage]) -> Vec<m::Linkage> {
+    linkage
+        .iter()
+        .map(|l| match l {
+            Linkage::Section { name, flags } => {
+                m::Linkage::Section(name.to_string(), flags.clone())
+            }
+            Linkage::Thread => m::Linkage::Thread,
+        })
+        .collect()
+}
+
+fn lower_loc(loc: &Loc) -> m::Loc {
+    match loc {
+        Loc::Pos { file, line, col } => m::Loc::Pos(file.to_string(), *line, *col),
+        Loc::Element { id } => m::Loc::Element(id.to_string()),
+    }
+}
+
 fn lower_visibility(visibility: &Visibility) -> m::Visibility {
     match visibility {
         Visibility::Public => m::Visibility::Public,
@@ -71,13 +147,22 @@ fn lower_visibility(visibility: &Visibility) -> m::Visibility {
 
 fn lower_type(r#type: &Type) -> m::Type {
     match r#type {
+        Type::Array { of: r#type, len } => m::Type::Array(Box::new(lower_type(r#type)), *len),
         Type::Bool => m::Type::Bool,
         Type::Double => m::Type::Double,
+        Type::Float32 => m::Type::Float32,
+        Type::Int8 => m::Type::Int8,
+        Type::Int16 => m::Type::Int16,
         Type::Int32 => m::Type::Int32,
         Type::Int64 => m::Type::Int64,
         Type::Ptr { to: r#type } => m::Type::Ptr(Some(Box::new(lower_type(r#type)))),
         Type::VoidPtr => m::Type::Ptr(None),
         Type::Str => m::Type::Str,
+        Type::Struct { name } => m::Type::Struct(name.to_string()),
+        Type::UInt8 => m::Type::UInt8,
+        Type::UInt16 => m::Type::UInt16,
+        Type::UInt32 => m::Type::UInt32,
+        Type::UInt64 => m::Type::UInt64,
     }
 }
 
@@ -91,17 +176,41 @@ fn lower_args(args: &[FuncArg]) -> Vec<m::FuncArg> {
         .collect()
 }
 
-fn lower_stmts(stmts: &[Stmt]) -> Res<Vec<m::Stmt>> {
-    stmts.iter().map(lower_stmt).collect()
+fn lower_stmts(stmts: &[Located<Stmt>]) -> Res<Vec<m::Stmt>> {
+    let mut lowered = Vec::<m::Stmt>::with_capacity(stmts.len());
+
+    for Located { node, loc } in stmts {
+        let loc = loc.as_ref().map(lower_loc);
+        let stmt = lower_stmt(node).map_err(|err| err.at(loc.as_ref()))?;
+
+        lowered.extend(loc.map(m::Stmt::Loc));
+        lowered.push(stmt);
+    }
+
+    Ok(lowered)
 }
 
 fn lower_stmt(stmt: &Stmt) -> Res<m::Stmt> {
     Ok(match stmt {
+        Stmt::Assign { name, value } => m::Stmt::Assign(name.to_string(), lower_expr(value)?),
+        Stmt::Break {} => m::Stmt::Break,
         Stmt::Cond { cases } => m::Stmt::Cond(lower_cases(cases)?),
+        Stmt::Continue {} => m::Stmt::Continue,
         Stmt::FuncCall { name, args } => m::Stmt::FuncCall(name.to_string(), lower_exprs(args)?),
+        Stmt::IndexAssign { name, index, value } => {
+            m::Stmt::IndexAssign(name.to_string(), lower_expr(index)?, lower_expr(value)?)
+        }
         Stmt::Ret { value: Some(value) } => m::Stmt::Ret(Some(lower_expr(value)?)),
         Stmt::Ret { value: None } => m::Stmt::Ret(None),
-        Stmt::VarDecl { name, value } => m::Stmt::VarDecl(name.to_string(), lower_expr(value)?),
+        Stmt::Store { ptr, value } => m::Stmt::Store(lower_expr(ptr)?, lower_expr(value)?),
+        Stmt::Unreachable {} => m::Stmt::Unreachable,
+        Stmt::VarDecl {
+            name,
+            value,
+            mutable: Some(true),
+        } => m::Stmt::VarDeclMut(name.to_string(), lower_expr(value)?),
+        Stmt::VarDecl { name, value, .. } => m::Stmt::VarDecl(name.to_string(), lower_expr(value)?),
+        Stmt::While { expr, stmts } => m::Stmt::While(lower_expr(expr)?, lower_stmts(stmts)?),
     })
 }
 
@@ -118,28 +227,77 @@ fn lower_exprs(exprs: &[Expr]) -> Res<Vec<m::Expr>> {
 
 fn lower_expr(expr: &Expr) -> Res<m::Expr> {
     Ok(match expr {
-        Expr::Eq { lhs, rhs } => m::Expr::Cmp(
-            m::Op::Eq,
-            Box::new(lower_expr(lhs)?),
-            Box::new(lower_expr(rhs)?),
-        ),
+        Expr::Add { lhs, rhs } => lower_arith(m::ArithOp::Add, lhs, rhs)?,
+        Expr::And { lhs, rhs } => {
+            m::Expr::And(Box::new(lower_expr(lhs)?), Box::new(lower_expr(rhs)?))
+        }
+        Expr::ArrayLit { r#type, elems } => {
+            m::Expr::ArrayLit(lower_type(r#type), lower_exprs(elems)?)
+        }
+        Expr::BitAnd { lhs, rhs } => lower_bitwise(m::BitOp::And, lhs, rhs)?,
+        Expr::BitOr { lhs, rhs } => lower_bitwise(m::BitOp::Or, lhs, rhs)?,
+        Expr::BitXor { lhs, rhs } => lower_bitwise(m::BitOp::Xor, lhs, rhs)?,
+        Expr::Cast { r#type, expr } => {
+            m::Expr::Cast(lower_type(r#type), Box::new(lower_expr(expr)?))
+        }
+        Expr::Eq { lhs, rhs } => lower_cmp(m::Op::Eq, lhs, rhs)?,
         Expr::Const { value, r#type } => match (value, r#type) {
             (Value::Bool(b), Type::Bool) => m::Expr::ConstBool(*b),
             (Value::Number(n), _) => lower_number(n, r#type)?,
             (Value::String(s), Type::Str) => m::Expr::ConstStr(s.to_string()),
             _ => {
-                return Err(Box::from("Unsupported value and type"));
+                return Err(Diagnostic::error("E0103", "Unsupported value and type"));
             }
         },
+        Expr::Div { lhs, rhs } => lower_arith(m::ArithOp::Div, lhs, rhs)?,
+        Expr::Field { expr, name, r#type } => m::Expr::Field(
+            Box::new(lower_expr(expr)?),
+            name.to_string(),
+            lower_type(r#type),
+        ),
         Expr::FuncCall { name, r#type, args } => {
             m::Expr::FuncCall(name.to_string(), lower_type(r#type), lower_exprs(args)?)
         }
-        Expr::Ne { lhs, rhs } => m::Expr::Cmp(
-            m::Op::Ne,
-            Box::new(lower_expr(lhs)?),
-            Box::new(lower_expr(rhs)?),
+        Expr::Ge { lhs, rhs } => lower_cmp(m::Op::Ge, lhs, rhs)?,
+        Expr::Gt { lhs, rhs } => lower_cmp(m::Op::Gt, lhs, rhs)?,
+        Expr::Index {
+            expr,
+            index,
+            r#type,
+        } => m::Expr::Index(
+            Box::new(lower_expr(expr)?),
+            Box::new(lower_expr(index)?),
+            lower_type(r#type),
         ),
+        Expr::Le { lhs, rhs } => lower_cmp(m::Op::Le, lhs, rhs)?,
+        Expr::Load { ptr, r#type } => m::Expr::Load(Box::new(lower_expr(ptr)?), lower_type(r#type)),
+        Expr::Lt { lhs, rhs } => lower_cmp(m::Op::Lt, lhs, rhs)?,
+        Expr::Mul { lhs, rhs } => lower_arith(m::ArithOp::Mul, lhs, rhs)?,
+        Expr::Ne { lhs, rhs } => lower_cmp(m::Op::Ne, lhs, rhs)?,
+        Expr::Neg { expr } => m::Expr::Neg(Box::new(lower_expr(expr)?)),
         Expr::Not { expr } => m::Expr::Not(Box::new(lower_expr(expr)?)),
+        Expr::Or { lhs, rhs } => {
+            m::Expr::Or(Box::new(lower_expr(lhs)?), Box::new(lower_expr(rhs)?))
+        }
+        Expr::PtrAdd { ptr, index } => {
+            m::Expr::PtrAdd(Box::new(lower_expr(ptr)?), Box::new(lower_expr(index)?))
+        }
+        Expr::Rem { lhs, rhs } => lower_arith(m::ArithOp::Rem, lhs, rhs)?,
+        Expr::Sar { lhs, rhs } => lower_bitwise(m::BitOp::Sar, lhs, rhs)?,
+        Expr::Shl { lhs, rhs } => lower_bitwise(m::BitOp::Shl, lhs, rhs)?,
+        Expr::Shr { lhs, rhs } => lower_bitwise(m::BitOp::Shr, lhs, rhs)?,
+        Expr::StructLit { r#type, fields } => m::Expr::StructLit(
+            lower_type(r#type),
+            fields
+                .iter()
+                .map(|f| Ok((f.name.to_string(), lower_expr(&f.value)?)))
+                .collect::<Res<_>>()?,
+        ),
+        Expr::Sub { lhs, rhs } => lower_arith(m::ArithOp::Sub, lhs, rhs)?,
+        Expr::UGe { lhs, rhs } => lower_cmp(m::Op::UGe, lhs, rhs)?,
+        Expr::UGt { lhs, rhs } => lower_cmp(m::Op::UGt, lhs, rhs)?,
+        Expr::ULe { lhs, rhs } => lower_cmp(m::Op::ULe, lhs, rhs)?,
+        Expr::ULt { lhs, rhs } => lower_cmp(m::Op::ULt, lhs, rhs)?,
         Expr::VarRef {
             name,
             r#type,
@@ -148,30 +306,57 @@ fn lower_expr(expr: &Expr) -> Res<m::Expr> {
     })
 }
 
-fn lower_number(num: &serde_json::value::Number, r#type: &Type) -> Res<m::Expr> {
-    fn as_i32(num: &serde_json::value::Number) -> Res<i32> {
-        num.as_i64()
-            .map(i32::try_from)
-            .and_then(|r| r.ok())
-            .ok_or_else(|| Box::from("Number is not an Int32"))
-    }
+fn lower_arith(op: m::ArithOp, lhs: &Expr, rhs: &Expr) -> Res<m::Expr> {
+    Ok(m::Expr::Arith(
+        op,
+        Box::new(lower_expr(lhs)?),
+        Box::new(lower_expr(rhs)?),
+    ))
+}
+
+fn lower_bitwise(op: m::BitOp, lhs: &Expr, rhs: &Expr) -> Res<m::Expr> {
+    Ok(m::Expr::Bitwise(
+        op,
+        Box::new(lower_expr(lhs)?),
+        Box::new(lower_expr(rhs)?),
+    ))
+}
 
-    fn as_i64(num: &serde_json::value::Number) -> Res<i64> {
+fn lower_cmp(op: m::Op, lhs: &Expr, rhs: &Expr) -> Res<m::Expr> {
+    Ok(m::Expr::Cmp(
+        op,
+        Box::new(lower_expr(lhs)?),
+        Box::new(lower_expr(rhs)?),
+    ))
+}
+
+fn lower_number(num: &serde_json::value::Number, r#type: &Type) -> Res<m::Expr> {
+    fn as_int<T: TryFrom<i128>>(num: &serde_json::value::Number, name: &str) -> Res<T> {
         num.as_i64()
-            .ok_or_else(|| Box::from("Number is not an Int64"))
+            .map(i128::from)
+            .or_else(|| num.as_u64().map(i128::from))
+            .and_then(|i| T::try_from(i).ok())
+            .ok_or_else(|| Diagnostic::error("E0104", format!("Number is not an {}", name)))
     }
 
     fn as_f64(num: &serde_json::value::Number) -> Res<f64> {
         num.as_f64()
-            .ok_or_else(|| Box::from("Number is not a Double"))
+            .ok_or_else(|| Diagnostic::error("E0105", "Number is not a Double"))
     }
 
     Ok(match (num, r#type) {
         (n, Type::Double) => m::Expr::ConstDouble(as_f64(n)?),
-        (n, Type::Int32) => m::Expr::ConstInt32(as_i32(n)?),
-        (n, Type::Int64) => m::Expr::ConstInt64(as_i64(n)?),
+        (n, Type::Float32) => m::Expr::ConstFloat32(as_f64(n)? as f32),
+        (n, Type::Int8) => m::Expr::ConstInt8(as_int(n, "Int8")?),
+        (n, Type::Int16) => m::Expr::ConstInt16(as_int(n, "Int16")?),
+        (n, Type::Int32) => m::Expr::ConstInt32(as_int(n, "Int32")?),
+        (n, Type::Int64) => m::Expr::ConstInt64(as_int(n, "Int64")?),
+        (n, Type::UInt8) => m::Expr::ConstUInt8(as_int(n, "UInt8")?),
+        (n, Type::UInt16) => m::Expr::ConstUInt16(as_int(n, "UInt16")?),
+        (n, Type::UInt32) => m::Expr::ConstUInt32(as_int(n, "UInt32")?),
+        (n, Type::UInt64) => m::Expr::ConstUInt64(as_int(n, "UInt64")?),
         _ => {
-            return Err(Box::from("Invalid number value and type"));
+            return Err(Diagnostic::error("E0106", "Invalid number value and type"));
         }
     })
 }
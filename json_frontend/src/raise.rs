@@ -21,52 +21,129 @@ fn raise_module(module: &m::Module) -> Module {
     }
 }
 
-fn raise_decls(decls: &[m::Decl]) -> Vec<Decl> {
-    decls.iter().map(raise_decl).collect()
+// A Loc is raised onto the node after it, one that does not describe a node is dropped
+fn raise_decls(decls: &[m::Decl]) -> Vec<Located<Decl>> {
+    m::located(decls)
+        .filter_map(|(loc, decl)| {
+            Some(Located {
+                node: raise_decl(decl)?,
+                loc: loc.map(raise_loc),
+            })
+        })
+        .collect()
 }
 
-fn raise_decl(decl: &m::Decl) -> Decl {
-    match decl {
-        m::Decl::FwdDecl(name, visibility, r#type, args, variadic) => Decl::FwdDecl {
+fn raise_decl(decl: &m::Decl) -> Option<Decl> {
+    let decl = match decl {
+        m::Decl::Const(name, visibility, linkage, value) => Decl::Const {
             name: name.to_string(),
             visibility: raise_visibility(visibility),
-            r#type: raise_opt_type(r#type),
-            args: raise_args(args),
-            variadic: raise_opt_bool(variadic),
+            linkage: raise_linkage(linkage),
+            value: raise_expr(value),
         },
-        m::Decl::FuncDecl(name, visibility, r#type, args, variadic, stmts) => Decl::FuncDecl {
+        m::Decl::FwdDecl(name, visibility, r#type, args, variadic, noreturn) => Decl::FwdDecl {
             name: name.to_string(),
             visibility: raise_visibility(visibility),
             r#type: raise_opt_type(r#type),
             args: raise_args(args),
             variadic: raise_opt_bool(variadic),
-            stmts: raise_stmts(stmts),
+            noreturn: raise_opt_bool(noreturn),
         },
-    }
+        m::Decl::FuncDecl(name, visibility, r#type, args, variadic, noreturn, stmts) => {
+            Decl::FuncDecl {
+                name: name.to_string(),
+                visibility: raise_visibility(visibility),
+                r#type: raise_opt_type(r#type),
+                args: raise_args(args),
+                variadic: raise_opt_bool(variadic),
+                noreturn: raise_opt_bool(noreturn),
+                stmts: raise_stmts(stmts),
+            }
+        }
+        m::Decl::Global(name, visibility, linkage, r#type, value) => Decl::Global {
+            name: name.to_string(),
+            visibility: raise_visibility(visibility),
+            linkage: raise_linkage(linkage),
+            r#type: raise_type(r#type),
+            value: value.as_ref().map(raise_expr),
+        },
+        m::Decl::Import(module) => Decl::Import {
+            module: module.to_string(),
+        },
+        m::Decl::Loc(_) => return None,
+        m::Decl::StructDecl(name, fields) => Decl::StructDecl {
+            name: name.to_string(),
+            fields: fields
+                .iter()
+                .map(|(name, r#type)| StructField {
+                    name: name.to_string(),
+                    r#type: raise_type(r#type),
+                })
+                .collect(),
+        },
+    };
+
+    Some(decl)
 }
 
-fn raise_stmts(stmts: &[m::Stmt]) -> Vec<Stmt> {
-    stmts.iter().map(raise_stmt).collect()
+fn raise_stmts(stmts: &[m::Stmt]) -> Vec<Located<Stmt>> {
+    m::located(stmts)
+        .filter_map(|(loc, stmt)| {
+            Some(Located {
+                node: raise_stmt(stmt)?,
+                loc: loc.map(raise_loc),
+            })
+        })
+        .collect()
 }
 
-fn raise_stmt(stmt: &m::Stmt) -> Stmt {
-    match stmt {
+fn raise_stmt(stmt: &m::Stmt) -> Option<Stmt> {
+    let stmt = match stmt {
+        m::Stmt::Assign(name, value) => Stmt::Assign {
+            name: name.to_string(),
+            value: raise_expr(value),
+        },
+        m::Stmt::Break => Stmt::Break {},
         m::Stmt::Cond(cases) => Stmt::Cond {
             cases: raise_cases(cases),
         },
+        m::Stmt::Continue => Stmt::Continue {},
         m::Stmt::FuncCall(name, args) => Stmt::FuncCall {
             name: name.to_string(),
             args: raise_exprs(args),
         },
+        m::Stmt::IndexAssign(name, index, value) => Stmt::IndexAssign {
+            name: name.to_string(),
+            index: raise_expr(index),
+            value: raise_expr(value),
+        },
+        m::Stmt::Loc(_) => return None,
         m::Stmt::Ret(Some(value)) => Stmt::Ret {
             value: Some(raise_expr(value)),
         },
         m::Stmt::Ret(None) => Stmt::Ret { value: None },
+        m::Stmt::Store(ptr, value) => Stmt::Store {
+            ptr: raise_expr(ptr),
+            value: raise_expr(value),
+        },
+        m::Stmt::Unreachable => Stmt::Unreachable {},
         m::Stmt::VarDecl(name, value) => Stmt::VarDecl {
             name: name.to_string(),
             value: raise_expr(value),
+            mutable: None,
         },
-    }
+        m::Stmt::VarDeclMut(name, value) => Stmt::VarDecl {
+            name: name.to_string(),
+            value: raise_expr(value),
+            mutable: Some(true),
+        },
+        m::Stmt::While(expr, stmts) => Stmt::While {
+            expr: raise_expr(expr),
+            stmts: raise_stmts(stmts),
+        },
+    };
+
+    Some(stmt)
 }
 
 fn raise_cases(cases: &[m::Case]) -> Vec<Case> {
@@ -85,14 +162,60 @@ fn raise_exprs(exprs: &[m::Expr]) -> Vec<Expr> {
 
 fn raise_expr(expr: &m::Expr) -> Expr {
     match expr {
-        m::Expr::Cmp(m::Op::Eq, lhs, rhs) => Expr::Eq {
+        m::Expr::Arith(op, lhs, rhs) => {
+            let lhs = Box::new(raise_expr(lhs));
+            let rhs = Box::new(raise_expr(rhs));
+
+            match op {
+                m::ArithOp::Add => Expr::Add { lhs, rhs },
+                m::ArithOp::Sub => Expr::Sub { lhs, rhs },
+                m::ArithOp::Mul => Expr::Mul { lhs, rhs },
+                m::ArithOp::Div => Expr::Div { lhs, rhs },
+                m::ArithOp::Rem => Expr::Rem { lhs, rhs },
+            }
+        }
+        m::Expr::And(lhs, rhs) => Expr::And {
             lhs: Box::new(raise_expr(lhs)),
             rhs: Box::new(raise_expr(rhs)),
         },
-        m::Expr::Cmp(m::Op::Ne, lhs, rhs) => Expr::Ne {
-            lhs: Box::new(raise_expr(lhs)),
-            rhs: Box::new(raise_expr(rhs)),
+        m::Expr::ArrayLit(r#type, elems) => Expr::ArrayLit {
+            r#type: raise_type(r#type),
+            elems: raise_exprs(elems),
         },
+        m::Expr::Bitwise(op, lhs, rhs) => {
+            let lhs = Box::new(raise_expr(lhs));
+            let rhs = Box::new(raise_expr(rhs));
+
+            match op {
+                m::BitOp::And => Expr::BitAnd { lhs, rhs },
+                m::BitOp::Or => Expr::BitOr { lhs, rhs },
+                m::BitOp::Xor => Expr::BitXor { lhs, rhs },
+                m::BitOp::Shl => Expr::Shl { lhs, rhs },
+                m::BitOp::Sar => Expr::Sar { lhs, rhs },
+                m::BitOp::Shr => Expr::Shr { lhs, rhs },
+            }
+        }
+        m::Expr::Cast(r#type, expr) => Expr::Cast {
+            r#type: raise_type(r#type),
+            expr: Box::new(raise_expr(expr)),
+        },
+        m::Expr::Cmp(op, lhs, rhs) => {
+            let lhs = Box::new(raise_expr(lhs));
+            let rhs = Box::new(raise_expr(rhs));
+
+            match op {
+                m::Op::Eq => Expr::Eq { lhs, rhs },
+                m::Op::Ne => Expr::Ne { lhs, rhs },
+                m::Op::Lt => Expr::Lt { lhs, rhs },
+                m::Op::Le => Expr::Le { lhs, rhs },
+                m::Op::Gt => Expr::Gt { lhs, rhs },
+                m::Op::Ge => Expr::Ge { lhs, rhs },
+                m::Op::ULt => Expr::ULt { lhs, rhs },
+                m::Op::ULe => Expr::ULe { lhs, rhs },
+                m::Op::UGt => Expr::UGt { lhs, rhs },
+                m::Op::UGe => Expr::UGe { lhs, rhs },
+            }
+        }
         m::Expr::ConstBool(b) => Expr::Const {
             value: Value::from(*b),
             r#type: Type::Bool,
@@ -101,6 +224,18 @@ fn raise_expr(expr: &m::Expr) -> Expr {
             value: Value::from(*d),
             r#type: Type::Double,
         },
+        m::Expr::ConstFloat32(f) => Expr::Const {
+            value: Value::from(*f),
+            r#type: Type::Float32,
+        },
+        m::Expr::ConstInt8(i) => Expr::Const {
+            value: Value::from(*i),
+            r#type: Type::Int8,
+        },
+        m::Expr::ConstInt16(i) => Expr::Const {
+            value: Value::from(*i),
+            r#type: Type::Int16,
+        },
         m::Expr::ConstInt32(i) => Expr::Const {
             value: Value::from(*i),
             r#type: Type::Int32,
@@ -113,14 +248,65 @@ fn raise_expr(expr: &m::Expr) -> Expr {
             value: Value::from(s.to_string()),
             r#type: Type::Str,
         },
+        m::Expr::ConstUInt8(i) => Expr::Const {
+            value: Value::from(*i),
+            r#type: Type::UInt8,
+        },
+        m::Expr::ConstUInt16(i) => Expr::Const {
+            value: Value::from(*i),
+            r#type: Type::UInt16,
+        },
+        m::Expr::ConstUInt32(i) => Expr::Const {
+            value: Value::from(*i),
+            r#type: Type::UInt32,
+        },
+        m::Expr::ConstUInt64(i) => Expr::Const {
+            value: Value::from(*i),
+            r#type: Type::UInt64,
+        },
+        m::Expr::Field(expr, name, r#type) => Expr::Field {
+            expr: Box::new(raise_expr(expr)),
+            name: name.to_string(),
+            r#type: raise_type(r#type),
+        },
         m::Expr::FuncCall(name, r#type, args) => Expr::FuncCall {
             name: name.to_string(),
             r#type: raise_type(r#type),
             args: raise_exprs(args),
         },
+        m::Expr::Index(expr, index, r#type) => Expr::Index {
+            expr: Box::new(raise_expr(expr)),
+            index: Box::new(raise_expr(index)),
+            r#type: raise_type(r#type),
+        },
+        m::Expr::Load(ptr, r#type) => Expr::Load {
+            ptr: Box::new(raise_expr(ptr)),
+            r#type: raise_type(r#type),
+        },
+        m::Expr::Neg(expr) => Expr::Neg {
+            expr: Box::new(raise_expr(expr)),
+        },
         m::Expr::Not(expr) => Expr::Not {
             expr: Box::new(raise_expr(expr)),
         },
+        m::Expr::Or(lhs, rhs) => Expr::Or {
+            lhs: Box::new(raise_expr(lhs)),
+            rhs: Box::new(raise_expr(rhs)),
+        },
+        m::Expr::PtrAdd(ptr, index) => Expr::PtrAdd {
+            ptr: Box::new(raise_expr(ptr)),
+            index: Box::new(raise_expr(index)),
+        },
+        m::Expr::StructLit(r#type, fields) => Expr::StructLit {
+            r#type: raise_type(r#type),
+            fields: fields
+                .iter()
+                .map(|(name, value)| FieldInit {
+                    name: name.to_string(),
+                    value: raise_expr(value),
+                })
+                .collect(),
+        },
         m::Expr::VarRef(name, r#type, byref) => Expr::VarRef {
             name: name.to_string(),
             r#type: raise_type(r#type),
@@ -146,6 +332,30 @@ fn raise_opt_bool(value: &bool) -> Option<bool> {
     }
 }
 
+fn raise_linkage(linkage: &[m::Linkage]) -> Vec<Linkage> {
+    linkage
+        .iter()
+        .map(|l| match l {
+            m::Linkage::Section(name, flags) => Linkage::Section {
+                name: name.to_string(),
+                flags: flags.clone(),
+            },
+            m::Linkage::Thread => Linkage::Thread,
+        })
+        .collect()
+}
+
+fn raise_loc(loc: &m::Loc) -> Loc {
+    match loc {
+        m::Loc::Element(id) => Loc::Element { id: id.to_string() },
+        m::Loc::Pos(file, line, col) => Loc::Pos {
+            file: file.to_string(),
+            line: *line,
+            col: *col,
+        },
+    }
+}
+
 fn raise_visibility(visibility: &m::Visibility) -> Visibility {
     match visibility {
         m::Visibility::Public => Visibility::Public,
@@ -155,8 +365,15 @@ fn raise_visibility(visibility: &m::Visibility) -> Visibility {
 
 fn raise_type(r#type: &m::Type) -> Type {
     match r#type {
+        m::Type::Array(r#type, len) => Type::Array {
+            of: Box::new(raise_type(r#type)),
+            len: *len,
+        },
         m::Type::Bool => Type::Bool,
         m::Type::Double => Type::Double,
+        m::Type::Float32 => Type::Float32,
+        m::Type::Int8 => Type::Int8,
+        m::Type::Int16 => Type::Int16,
         m::Type::Int32 => Type::Int32,
         m::Type::Int64 => Type::Int64,
         m::Type::Ptr(Some(r#type)) => Type::Ptr {
@@ -164,6 +381,13 @@ fn raise_type(r#type: &m::Type) -> Type {
         },
         m::Type::Ptr(None) => Type::VoidPtr,
         m::Type::Str => Type::Str,
+        m::Type::Struct(name) => Type::Struct {
+            name: name.to_string(),
+        },
+        m::Type::UInt8 => Type::UInt8,
+        m::Type::UInt16 => Type::UInt16,
+        m::Type::UInt32 => Type::UInt32,
+        m::Type::UInt64 => Type::UInt64,
     }
 }
 
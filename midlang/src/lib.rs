@@ -7,45 +7,170 @@ pub struct Module {
 }
 
 pub type Case = (Expr, Vec<Stmt>);
+pub type Column = u32;
+pub type FieldInit = (String, Expr);
 pub type FuncArg = (String, Type);
+pub type Line = u32;
+pub type Noreturn = bool;
+pub type StructField = (String, Type);
 pub type Variadic = bool;
 
 pub enum Decl {
-    FwdDecl(String, Visibility, Option<Type>, Vec<FuncArg>, Variadic),
+    Const(String, Visibility, Vec<Linkage>, Expr),
+    FwdDecl(
+        String,
+        Visibility,
+        Option<Type>,
+        Vec<FuncArg>,
+        Variadic,
+        Noreturn,
+    ),
     FuncDecl(
         String,
         Visibility,
         Option<Type>,
         Vec<FuncArg>,
         Variadic,
+        Noreturn,
         Vec<Stmt>,
     ),
+    Global(String, Visibility, Vec<Linkage>, Type, Option<Expr>),
+    // Makes every public func of the named module callable without forward declaring each one
+    Import(String),
+    Loc(Loc),
+    StructDecl(String, Vec<StructField>),
 }
 
 pub enum Stmt {
+    Assign(String, Expr),
+    Break,
     Cond(Vec<Case>),
+    Continue,
     FuncCall(String, Vec<Expr>),
+    IndexAssign(String, Expr, Expr),
+    Loc(Loc),
     Ret(Option<Expr>),
+    Store(Expr, Expr),
+    Unreachable,
     VarDecl(String, Expr),
+    VarDeclMut(String, Expr),
+    While(Expr, Vec<Stmt>),
+}
+
+// Where the decl or stmt that follows a Loc came from, either a position in a source file or an
+// opaque id for frontends whose sources are not text
+#[derive(Clone, Debug, PartialEq)]
+pub enum Loc {
+    Element(String),
+    Pos(String, Line, Option<Column>),
+}
+
+pub trait Locatable {
+    fn loc(&self) -> Option<&Loc>;
+}
+
+impl Locatable for Decl {
+    fn loc(&self) -> Option<&Loc> {
+        match self {
+            Decl::Loc(loc) => Some(loc),
+            _ => None,
+        }
+    }
+}
+
+impl Locatable for Stmt {
+    fn loc(&self) -> Option<&Loc> {
+        match self {
+            Stmt::Loc(loc) => Some(loc),
+            _ => None,
+        }
+    }
+}
+
+// Pairs each decl or stmt with the Loc ahead of it, if any. Passes walk nodes through this so a
+// Loc is never counted or matched as a node of its own. A Loc only says where the node after it
+// came from, so one with nothing after it is dropped and of several in a row the last one wins
+pub fn located<T: Locatable>(nodes: &[T]) -> impl Iterator<Item = (Option<&Loc>, &T)> {
+    let mut loc = None;
+
+    nodes.iter().filter_map(move |node| match node.loc() {
+        Some(node_loc) => {
+            loc = Some(node_loc);
+            None
+        }
+        None => Some((loc.take(), node)),
+    })
 }
 
 #[derive(Debug)]
 pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Arith(ArithOp, Box<Expr>, Box<Expr>),
+    ArrayLit(Type, Vec<Expr>),
+    Bitwise(BitOp, Box<Expr>, Box<Expr>),
+    Cast(Type, Box<Expr>),
     Cmp(Op, Box<Expr>, Box<Expr>),
     ConstBool(bool),
     ConstDouble(f64),
+    ConstFloat32(f32),
+    ConstInt8(i8),
+    ConstInt16(i16),
     ConstInt32(i32),
     ConstInt64(i64),
     ConstStr(String),
+    ConstUInt8(u8),
+    ConstUInt16(u16),
+    ConstUInt32(u32),
+    ConstUInt64(u64),
+    Field(Box<Expr>, String, Type),
     FuncCall(String, Type, Vec<Expr>),
+    Index(Box<Expr>, Box<Expr>, Type),
+    Load(Box<Expr>, Type),
+    Neg(Box<Expr>),
     Not(Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    PtrAdd(Box<Expr>, Box<Expr>),
+    StructLit(Type, Vec<FieldInit>),
     VarRef(String, Type, bool),
 }
 
+#[derive(Debug)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug)]
+pub enum BitOp {
+    And,
+    Or,
+    Xor,
+    Shl,
+    Sar,
+    Shr,
+}
+
 #[derive(Debug)]
 pub enum Op {
     Eq,
     Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    ULt,
+    ULe,
+    UGt,
+    UGe,
+}
+
+#[derive(PartialEq)]
+pub enum Linkage {
+    Section(String, Option<String>),
+    Thread,
 }
 
 #[derive(PartialEq)]
@@ -56,35 +181,131 @@ pub enum Visibility {
 
 #[derive(Debug, PartialEq)]
 pub enum Type {
+    Array(Box<Type>, usize),
     Bool,
     Double,
+    Float32,
+    Int8,
+    Int16,
     Int32,
     Int64,
     Ptr(Option<Box<Type>>),
     Str,
+    Struct(String),
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
 }
 
 impl Expr {
     pub fn r#type(&self) -> &Type {
         match self {
+            Self::And(_, _) => &Type::Bool,
+            Self::Arith(_, lhs, _) => lhs.r#type(),
+            Self::ArrayLit(r#type, _) => r#type,
+            Self::Bitwise(_, lhs, _) => lhs.r#type(),
+            Self::Cast(r#type, _) => r#type,
             Self::Cmp(_, _, _) => &Type::Bool,
             Self::ConstBool(_) => &Type::Bool,
             Self::ConstDouble(_) => &Type::Double,
+            Self::ConstFloat32(_) => &Type::Float32,
+            Self::ConstInt8(_) => &Type::Int8,
+            Self::ConstInt16(_) => &Type::Int16,
             Self::ConstInt32(_) => &Type::Int32,
             Self::ConstInt64(_) => &Type::Int64,
             Self::ConstStr(_) => &Type::Str,
+            Self::ConstUInt8(_) => &Type::UInt8,
+            Self::ConstUInt16(_) => &Type::UInt16,
+            Self::ConstUInt32(_) => &Type::UInt32,
+            Self::ConstUInt64(_) => &Type::UInt64,
+            Self::Field(_, _, r#type) => r#type,
             Self::FuncCall(_, r#type, _) => r#type,
+            Self::Index(_, _, r#type) => r#type,
+            Self::Load(_, r#type) => r#type,
+            Self::Neg(expr) => expr.r#type(),
             Self::Not(_) => &Type::Bool,
+            Self::Or(_, _) => &Type::Bool,
+            Self::PtrAdd(ptr, _) => ptr.r#type(),
+            Self::StructLit(r#type, _) => r#type,
             Self::VarRef(_, r#type, _) => r#type,
         }
     }
 }
 
+impl Type {
+    pub fn is_float(&self) -> bool {
+        matches!(self, Self::Double | Self::Float32)
+    }
+
+    pub fn is_int(&self) -> bool {
+        self.is_signed_int() || self.is_unsigned_int()
+    }
+
+    pub fn is_number(&self) -> bool {
+        self.is_int() || self.is_float()
+    }
+
+    pub fn is_signed_int(&self) -> bool {
+        matches!(self, Self::Int8 | Self::Int16 | Self::Int32 | Self::Int64)
+    }
+
+    pub fn is_unsigned_int(&self) -> bool {
+        matches!(
+            self,
+            Self::UInt8 | Self::UInt16 | Self::UInt32 | Self::UInt64
+        )
+    }
+}
+
+impl Display for ArithOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Add => write!(f, "add"),
+            Self::Sub => write!(f, "sub"),
+            Self::Mul => write!(f, "mul"),
+            Self::Div => write!(f, "div"),
+            Self::Rem => write!(f, "rem"),
+        }
+    }
+}
+
+impl Display for BitOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::And => write!(f, "and"),
+            Self::Or => write!(f, "or"),
+            Self::Xor => write!(f, "xor"),
+            Self::Shl => write!(f, "shl"),
+            Self::Sar => write!(f, "sar"),
+            Self::Shr => write!(f, "shr"),
+        }
+    }
+}
+
+impl Display for Loc {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Element(id) => write!(f, "element '{}'", id),
+            Self::Pos(file, line, None) => write!(f, "{}:{}", file, line),
+            Self::Pos(file, line, Some(column)) => write!(f, "{}:{}:{}", file, line, column),
+        }
+    }
+}
+
 impl Display for Op {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::Eq => write!(f, "eq"),
             Self::Ne => write!(f, "ne"),
+            Self::Lt => write!(f, "lt"),
+            Self::Le => write!(f, "le"),
+            Self::Gt => write!(f, "gt"),
+            Self::Ge => write!(f, "ge"),
+            Self::ULt => write!(f, "ult"),
+            Self::ULe => write!(f, "ule"),
+            Self::UGt => write!(f, "ugt"),
+            Self::UGe => write!(f, "uge"),
         }
     }
 }
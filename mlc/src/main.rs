@@ -1,6 +1,8 @@
-use std::error::Error;
+use std::process::ExitCode;
+use std::thread;
 
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -9,27 +11,105 @@ struct Args {
     #[arg(short, long)]
     build_dir: String,
     #[arg(short, long)]
-    ninja: String,
+    ninja: Option<String>,
+    #[arg(long)]
+    capture_build_output: bool,
+    #[arg(long)]
+    jobs: Option<usize>,
+    #[arg(long, default_value = "timestamps")]
+    up_to_date: compiler::UpToDate,
     #[arg(short, long = "library")]
     libraries: Option<Vec<String>>,
     #[arg(short = 'L', long = "library-path")]
     library_paths: Option<Vec<String>>,
     #[arg(short, long)]
     output: Option<String>,
+    #[arg(short, long, default_value = "exe")]
+    kind: compiler::BuildKind,
+    /// How to report a var that redeclares an arg or an earlier var, in the same scope or an
+    /// enclosing one. A var shadowing a global is always an error
+    #[arg(long, default_value = "warn")]
+    shadowing: compiler::Level,
 }
 
-const DEFAULT_OUTPUT: &str = "a.out";
+// Object builds leave one object per module in the build dir so they have no output to name
+fn default_output(kind: compiler::BuildKind) -> &'static str {
+    match kind {
+        compiler::BuildKind::Executable => "a.out",
+        compiler::BuildKind::StaticLib => "liba.a",
+        compiler::BuildKind::SharedLib => "liba.so",
+        compiler::BuildKind::Object => "",
+    }
+}
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> ExitCode {
     let args = Args::parse();
+
+    if args.kind == compiler::BuildKind::Object && args.output.is_some() {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--output cannot be used with --kind object, each module is built into its own \
+                 object in the build dir",
+            )
+            .exit();
+    }
     let frontend = json_frontend::new(&args.json_file);
 
     let libraries = args.libraries.unwrap_or_else(Vec::new);
     let library_paths = args.library_paths.unwrap_or_else(Vec::new);
-    let output = args.output.unwrap_or_else(|| DEFAULT_OUTPUT.to_string());
+    let output = args
+        .output
+        .unwrap_or_else(|| default_output(args.kind).to_string());
     let backend = qbe_backend::new(&libraries, &library_paths, &output);
 
-    let compiler = compiler::new(&frontend, &backend, &args.build_dir, &args.ninja);
+    let rules = compiler::Rules {
+        shadowing: args.shadowing,
+    };
+
+    // Without a ninja binary the build graph is run in process
+    let executor: Box<dyn compiler::Executor> = match &args.ninja {
+        Some(ninja) => Box::new(compiler::ninja_executor(ninja, args.capture_build_output)),
+        None => {
+            let jobs = args
+                .jobs
+                .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+
+            Box::new(compiler::native_executor(
+                jobs,
+                args.up_to_date,
+                args.capture_build_output,
+            ))
+        }
+    };
+
+    let compiler = compiler::new(
+        &frontend,
+        &backend,
+        executor.as_ref(),
+        &args.build_dir,
+        args.kind,
+        rules,
+    );
+
+    match compiler.compile() {
+        Ok(warnings) => {
+            print_diagnostics(&warnings);
+            ExitCode::SUCCESS
+        }
+        Err(diagnostics) => {
+            print_diagnostics(&diagnostics);
+
+            let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+            eprintln!("Compilation failed with {} error(s)", errors);
+
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    compiler.compile()
+fn print_diagnostics(diagnostics: &compiler::Diagnostics) {
+    for diagnostic in diagnostics {
+        eprintln!("{}\n", diagnostic);
+    }
 }
@@ -0,0 +1,237 @@
+use midlang::*;
+
+fn arith_module(name: &str, var_decl: Stmt, cases: Vec<(Expr, Expr)>) -> Vec<Module> {
+    let cases = cases
+        .into_iter()
+        .enumerate()
+        .map(|(i, (lhs, rhs))| {
+            (
+                Expr::Cmp(Op::Ne, Box::new(lhs), Box::new(rhs)),
+                vec![Stmt::FuncCall(
+                    "exit".to_string(),
+                    vec![Expr::ConstInt32(i as i32 + 1)],
+                )],
+            )
+        })
+        .collect();
+
+    vec![Module {
+        name: name.to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    var_decl,
+                    Stmt::Cond(cases),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr(format!("{} works!", name))],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
+
+fn arith(op: ArithOp, lhs: Expr, rhs: Expr) -> Expr {
+    Expr::Arith(op, Box::new(lhs), Box::new(rhs))
+}
+
+fn negate(expr: Expr) -> Expr {
+    Expr::Neg(Box::new(expr))
+}
+
+fn x(r#type: Type) -> Expr {
+    Expr::VarRef("x".to_string(), r#type, false)
+}
+
+pub fn add() -> Vec<Module> {
+    arith_module(
+        "add",
+        Stmt::VarDecl(
+            "x".to_string(),
+            arith(ArithOp::Add, Expr::ConstInt32(2), Expr::ConstInt32(3)),
+        ),
+        vec![
+            (x(Type::Int32), Expr::ConstInt32(5)),
+            (
+                arith(ArithOp::Add, Expr::ConstInt64(2), Expr::ConstInt64(3)),
+                Expr::ConstInt64(5),
+            ),
+            (
+                arith(
+                    ArithOp::Add,
+                    Expr::ConstDouble(1.5),
+                    Expr::ConstDouble(2.25),
+                ),
+                Expr::ConstDouble(3.75),
+            ),
+        ],
+    )
+}
+
+pub fn sub() -> Vec<Module> {
+    arith_module(
+        "sub",
+        Stmt::VarDecl(
+            "x".to_string(),
+            arith(ArithOp::Sub, Expr::ConstInt32(5), Expr::ConstInt32(3)),
+        ),
+        vec![
+            (x(Type::Int32), Expr::ConstInt32(2)),
+            (
+                arith(ArithOp::Sub, Expr::ConstInt64(3), Expr::ConstInt64(5)),
+                Expr::ConstInt64(-2),
+            ),
+            (
+                arith(
+                    ArithOp::Sub,
+                    Expr::ConstDouble(3.75),
+                    Expr::ConstDouble(1.5),
+                ),
+                Expr::ConstDouble(2.25),
+            ),
+        ],
+    )
+}
+
+pub fn mul() -> Vec<Module> {
+    arith_module(
+        "mul",
+        Stmt::VarDecl(
+            "x".to_string(),
+            arith(ArithOp::Mul, Expr::ConstInt32(6), Expr::ConstInt32(7)),
+        ),
+        vec![
+            (x(Type::Int32), Expr::ConstInt32(42)),
+            (
+                arith(ArithOp::Mul, Expr::ConstInt64(-6), Expr::ConstInt64(7)),
+                Expr::ConstInt64(-42),
+            ),
+            (
+                arith(ArithOp::Mul, Expr::ConstDouble(1.5), Expr::ConstDouble(4.0)),
+                Expr::ConstDouble(6.0),
+            ),
+        ],
+    )
+}
+
+pub fn div() -> Vec<Module> {
+    arith_module(
+        "div",
+        Stmt::VarDecl(
+            "x".to_string(),
+            arith(ArithOp::Div, Expr::ConstInt32(42), Expr::ConstInt32(6)),
+        ),
+        vec![
+            (x(Type::Int32), Expr::ConstInt32(7)),
+            (
+                arith(ArithOp::Div, Expr::ConstInt64(-42), Expr::ConstInt64(6)),
+                Expr::ConstInt64(-7),
+            ),
+            (
+                arith(ArithOp::Div, Expr::ConstDouble(7.5), Expr::ConstDouble(2.5)),
+                Expr::ConstDouble(3.0),
+            ),
+        ],
+    )
+}
+
+pub fn rem() -> Vec<Module> {
+    arith_module(
+        "rem",
+        Stmt::VarDecl(
+            "x".to_string(),
+            arith(ArithOp::Rem, Expr::ConstInt32(17), Expr::ConstInt32(5)),
+        ),
+        vec![
+            (x(Type::Int32), Expr::ConstInt32(2)),
+            (
+                arith(ArithOp::Rem, Expr::ConstInt64(-17), Expr::ConstInt64(5)),
+                Expr::ConstInt64(-2),
+            ),
+        ],
+    )
+}
+
+pub fn neg() -> Vec<Module> {
+    arith_module(
+        "neg",
+        Stmt::VarDecl("x".to_string(), negate(Expr::ConstInt32(5))),
+        vec![
+            (x(Type::Int32), Expr::ConstInt32(-5)),
+            (negate(Expr::ConstInt64(-5)), Expr::ConstInt64(5)),
+            (
+                negate(arith(
+                    ArithOp::Add,
+                    Expr::ConstDouble(1.0),
+                    Expr::ConstDouble(0.5),
+                )),
+                Expr::ConstDouble(-1.5),
+            ),
+        ],
+    )
+}
+
+fn bitwise_op(op: BitOp, lhs: Expr, rhs: Expr) -> Expr {
+    Expr::Bitwise(op, Box::new(lhs), Box::new(rhs))
+}
+
+pub fn bitwise() -> Vec<Module> {
+    arith_module(
+        "bitwise",
+        Stmt::VarDecl(
+            "x".to_string(),
+            bitwise_op(BitOp::And, Expr::ConstInt32(12), Expr::ConstInt32(10)),
+        ),
+        vec![
+            (x(Type::Int32), Expr::ConstInt32(8)),
+            (
+                bitwise_op(BitOp::Or, Expr::ConstInt32(12), Expr::ConstInt32(10)),
+                Expr::ConstInt32(14),
+            ),
+            (
+                bitwise_op(BitOp::Xor, Expr::ConstInt64(12), Expr::ConstInt64(10)),
+                Expr::ConstInt64(6),
+            ),
+            (
+                bitwise_op(BitOp::Shl, Expr::ConstInt32(1), Expr::ConstInt32(4)),
+                Expr::ConstInt32(16),
+            ),
+            (
+                bitwise_op(BitOp::Sar, Expr::ConstInt32(-16), Expr::ConstInt32(2)),
+                Expr::ConstInt32(-4),
+            ),
+            (
+                bitwise_op(BitOp::Shr, Expr::ConstInt32(-16), Expr::ConstInt32(28)),
+                Expr::ConstInt32(15),
+            ),
+            (
+                bitwise_op(BitOp::Shr, Expr::ConstInt64(-1), Expr::ConstInt64(60)),
+                Expr::ConstInt64(15),
+            ),
+        ],
+    )
+}
@@ -0,0 +1,183 @@
+use midlang::*;
+
+fn int32_ptr() -> Type {
+    Type::Ptr(Some(Box::new(Type::Int32)))
+}
+
+fn int32_array() -> Type {
+    Type::Array(Box::new(Type::Int32), 4)
+}
+
+fn var_ref(name: &str, r#type: Type) -> Box<Expr> {
+    Box::new(Expr::VarRef(name.to_string(), r#type, false))
+}
+
+fn inc(name: &str) -> Stmt {
+    Stmt::Assign(
+        name.to_string(),
+        Expr::Arith(
+            ArithOp::Add,
+            var_ref(name, Type::Int32),
+            Box::new(Expr::ConstInt32(1)),
+        ),
+    )
+}
+
+fn count_to_n(stmts: Vec<Stmt>) -> Stmt {
+    let mut stmts = stmts;
+    stmts.push(inc("i"));
+
+    Stmt::While(
+        Expr::Cmp(Op::Lt, var_ref("i", Type::Int32), var_ref("n", Type::Int32)),
+        stmts,
+    )
+}
+
+fn exit_case(expr: Expr, rhs: i32, status: i32) -> Case {
+    (
+        Expr::Cmp(Op::Ne, Box::new(expr), Box::new(Expr::ConstInt32(rhs))),
+        vec![Stmt::FuncCall(
+            "exit".to_string(),
+            vec![Expr::ConstInt32(status)],
+        )],
+    )
+}
+
+fn index(name: &str, index: Expr) -> Expr {
+    Expr::Index(var_ref(name, int32_array()), Box::new(index), Type::Int32)
+}
+
+fn sum(name: &str) -> Expr {
+    Expr::FuncCall(
+        "sum".to_string(),
+        Type::Int32,
+        vec![
+            Expr::VarRef(name.to_string(), int32_array(), true),
+            Expr::ConstInt32(4),
+        ],
+    )
+}
+
+pub fn arrays() -> Vec<Module> {
+    vec![Module {
+        name: "arrays".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "sum".to_string(),
+                Visibility::Private,
+                Some(Type::Int32),
+                vec![
+                    ("p".to_string(), int32_ptr()),
+                    ("n".to_string(), Type::Int32),
+                ],
+                false,
+                false,
+                vec![
+                    Stmt::VarDeclMut("total".to_string(), Expr::ConstInt32(0)),
+                    Stmt::VarDeclMut("i".to_string(), Expr::ConstInt32(0)),
+                    count_to_n(vec![Stmt::Assign(
+                        "total".to_string(),
+                        Expr::Arith(
+                            ArithOp::Add,
+                            var_ref("total", Type::Int32),
+                            Box::new(Expr::Load(
+                                Box::new(Expr::PtrAdd(
+                                    var_ref("p", int32_ptr()),
+                                    var_ref("i", Type::Int32),
+                                )),
+                                Type::Int32,
+                            )),
+                        ),
+                    )]),
+                    Stmt::Ret(Some(*var_ref("total", Type::Int32))),
+                ],
+            ),
+            Decl::FuncDecl(
+                "fill".to_string(),
+                Visibility::Private,
+                None,
+                vec![
+                    ("p".to_string(), int32_ptr()),
+                    ("n".to_string(), Type::Int32),
+                    ("v".to_string(), Type::Int32),
+                ],
+                false,
+                false,
+                vec![
+                    Stmt::VarDeclMut("i".to_string(), Expr::ConstInt32(0)),
+                    count_to_n(vec![Stmt::Store(
+                        Expr::PtrAdd(var_ref("p", int32_ptr()), var_ref("i", Type::Int32)),
+                        *var_ref("v", Type::Int32),
+                    )]),
+                    Stmt::Ret(None),
+                ],
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::VarDeclMut(
+                        "xs".to_string(),
+                        Expr::ArrayLit(
+                            int32_array(),
+                            vec![
+                                Expr::ConstInt32(1),
+                                Expr::ConstInt32(2),
+                                Expr::ConstInt32(3),
+                                Expr::ConstInt32(4),
+                            ],
+                        ),
+                    ),
+                    Stmt::IndexAssign("xs".to_string(), Expr::ConstInt32(2), Expr::ConstInt32(10)),
+                    Stmt::VarDeclMut("ys".to_string(), *var_ref("xs", int32_array())),
+                    Stmt::VarDecl("i".to_string(), Expr::ConstInt32(0)),
+                    Stmt::IndexAssign(
+                        "xs".to_string(),
+                        *var_ref("i", Type::Int32),
+                        Expr::ConstInt32(5),
+                    ),
+                    Stmt::FuncCall(
+                        "fill".to_string(),
+                        vec![
+                            Expr::VarRef("ys".to_string(), int32_array(), true),
+                            Expr::ConstInt32(2),
+                            Expr::ConstInt32(7),
+                        ],
+                    ),
+                    Stmt::Cond(vec![
+                        exit_case(index("xs", *var_ref("i", Type::Int32)), 5, 1),
+                        exit_case(index("xs", Expr::ConstInt64(2)), 10, 2),
+                        exit_case(sum("xs"), 21, 3),
+                        exit_case(sum("ys"), 28, 4),
+                        exit_case(index("ys", Expr::ConstInt32(3)), 4, 5),
+                    ]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("arrays work!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
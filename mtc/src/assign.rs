@@ -0,0 +1,109 @@
+use midlang::*;
+
+pub fn assign() -> Vec<Module> {
+    vec![Module {
+        name: "assign".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::VarDeclMut("i".to_string(), Expr::ConstInt32(0)),
+                    Stmt::VarDeclMut("sum".to_string(), Expr::ConstInt32(0)),
+                    Stmt::While(
+                        Expr::Cmp(
+                            Op::Lt,
+                            Box::new(Expr::VarRef("i".to_string(), Type::Int32, false)),
+                            Box::new(Expr::ConstInt32(10)),
+                        ),
+                        vec![
+                            Stmt::Assign(
+                                "i".to_string(),
+                                Expr::Arith(
+                                    ArithOp::Add,
+                                    Box::new(Expr::VarRef("i".to_string(), Type::Int32, false)),
+                                    Box::new(Expr::ConstInt32(1)),
+                                ),
+                            ),
+                            Stmt::Cond(vec![(
+                                Expr::Cmp(
+                                    Op::Eq,
+                                    Box::new(Expr::Arith(
+                                        ArithOp::Rem,
+                                        Box::new(Expr::VarRef("i".to_string(), Type::Int32, false)),
+                                        Box::new(Expr::ConstInt32(2)),
+                                    )),
+                                    Box::new(Expr::ConstInt32(0)),
+                                ),
+                                vec![Stmt::Continue],
+                            )]),
+                            Stmt::Assign(
+                                "sum".to_string(),
+                                Expr::Arith(
+                                    ArithOp::Add,
+                                    Box::new(Expr::VarRef("sum".to_string(), Type::Int32, false)),
+                                    Box::new(Expr::VarRef("i".to_string(), Type::Int32, false)),
+                                ),
+                            ),
+                        ],
+                    ),
+                    Stmt::Cond(vec![(
+                        Expr::Cmp(
+                            Op::Ne,
+                            Box::new(Expr::VarRef("sum".to_string(), Type::Int32, false)),
+                            Box::new(Expr::ConstInt32(25)),
+                        ),
+                        vec![Stmt::FuncCall(
+                            "exit".to_string(),
+                            vec![Expr::ConstInt32(1)],
+                        )],
+                    )]),
+                    Stmt::VarDeclMut(
+                        "msg".to_string(),
+                        Expr::ConstStr("assign fails!".to_string()),
+                    ),
+                    Stmt::VarDecl(
+                        "works".to_string(),
+                        Expr::ConstStr("assign works!".to_string()),
+                    ),
+                    Stmt::Cond(vec![(
+                        Expr::Cmp(
+                            Op::Eq,
+                            Box::new(Expr::VarRef("sum".to_string(), Type::Int32, false)),
+                            Box::new(Expr::ConstInt32(25)),
+                        ),
+                        vec![Stmt::Assign(
+                            "msg".to_string(),
+                            Expr::VarRef("works".to_string(), Type::Str, false),
+                        )],
+                    )]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::VarRef("msg".to_string(), Type::Str, false)],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
@@ -0,0 +1,110 @@
+use midlang::*;
+
+fn cast(r#type: Type, expr: Expr) -> Expr {
+    Expr::Cast(r#type, Box::new(expr))
+}
+
+pub fn casts() -> Vec<Module> {
+    let cases = [
+        (
+            cast(
+                Type::Int64,
+                Expr::VarRef("x".to_string(), Type::Int32, false),
+            ),
+            Expr::ConstInt64(-5),
+        ),
+        (
+            cast(Type::Int32, Expr::ConstInt64(4294967298)),
+            Expr::ConstInt32(2),
+        ),
+        (
+            cast(Type::Double, Expr::ConstInt32(3)),
+            Expr::ConstDouble(3.0),
+        ),
+        (
+            cast(Type::Double, Expr::ConstInt64(-7)),
+            Expr::ConstDouble(-7.0),
+        ),
+        (
+            cast(Type::Int32, Expr::ConstDouble(-2.75)),
+            Expr::ConstInt32(-2),
+        ),
+        (
+            cast(Type::Int64, Expr::ConstDouble(1e10)),
+            Expr::ConstInt64(10000000000),
+        ),
+        (
+            cast(Type::Int32, Expr::ConstBool(true)),
+            Expr::ConstInt32(1),
+        ),
+        (
+            cast(Type::Int64, Expr::ConstBool(true)),
+            Expr::ConstInt64(1),
+        ),
+        (
+            cast(Type::Bool, Expr::ConstInt32(42)),
+            Expr::ConstBool(true),
+        ),
+        (
+            cast(Type::Bool, Expr::ConstInt64(0)),
+            Expr::ConstBool(false),
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (lhs, rhs))| {
+        (
+            Expr::Cmp(Op::Ne, Box::new(lhs), Box::new(rhs)),
+            vec![Stmt::FuncCall(
+                "exit".to_string(),
+                vec![Expr::ConstInt32(i as i32 + 1)],
+            )],
+        )
+    })
+    .collect();
+
+    vec![Module {
+        name: "casts".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "printf".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("fmt".to_string(), Type::Str)],
+                true,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::VarDecl("x".to_string(), Expr::ConstInt32(-5)),
+                    Stmt::Cond(cases),
+                    Stmt::FuncCall(
+                        "printf".to_string(),
+                        vec![
+                            Expr::ConstStr("casts work! %ld\n".to_string()),
+                            cast(
+                                Type::Int64,
+                                Expr::VarRef("x".to_string(), Type::Int32, false),
+                            ),
+                        ],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
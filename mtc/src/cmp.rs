@@ -10,6 +10,7 @@ pub fn cmp() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("s".to_string(), Type::Str)],
                 false,
+                false,
             ),
             Decl::FwdDecl(
                 "exit".to_string(),
@@ -17,6 +18,7 @@ pub fn cmp() -> Vec<Module> {
                 None,
                 vec![("status".to_string(), Type::Int32)],
                 false,
+                false,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -24,6 +26,7 @@ pub fn cmp() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![
                     Stmt::Cond(vec![
                         (
@@ -70,3 +73,121 @@ pub fn cmp() -> Vec<Module> {
         ],
     }]
 }
+
+pub fn ord() -> Vec<Module> {
+    vec![Module {
+        name: "ord".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::Cond(vec![
+                        (
+                            Expr::Cmp(
+                                Op::Ge,
+                                Box::new(Expr::ConstInt32(1)),
+                                Box::new(Expr::ConstInt32(2)),
+                            ),
+                            vec![Stmt::FuncCall(
+                                "exit".to_string(),
+                                vec![Expr::ConstInt32(1)],
+                            )],
+                        ),
+                        (
+                            Expr::Cmp(
+                                Op::Gt,
+                                Box::new(Expr::ConstInt32(2)),
+                                Box::new(Expr::ConstInt32(2)),
+                            ),
+                            vec![Stmt::FuncCall(
+                                "exit".to_string(),
+                                vec![Expr::ConstInt32(2)],
+                            )],
+                        ),
+                        (
+                            Expr::Cmp(
+                                Op::Le,
+                                Box::new(Expr::ConstInt64(3)),
+                                Box::new(Expr::ConstInt64(2)),
+                            ),
+                            vec![Stmt::FuncCall(
+                                "exit".to_string(),
+                                vec![Expr::ConstInt32(3)],
+                            )],
+                        ),
+                        (
+                            Expr::Cmp(
+                                Op::Lt,
+                                Box::new(Expr::ConstDouble(2.5)),
+                                Box::new(Expr::ConstDouble(1.5)),
+                            ),
+                            vec![Stmt::FuncCall(
+                                "exit".to_string(),
+                                vec![Expr::ConstInt32(4)],
+                            )],
+                        ),
+                        (
+                            Expr::Cmp(
+                                Op::ULt,
+                                Box::new(Expr::ConstInt32(-1)),
+                                Box::new(Expr::ConstInt32(1)),
+                            ),
+                            vec![Stmt::FuncCall(
+                                "exit".to_string(),
+                                vec![Expr::ConstInt32(5)],
+                            )],
+                        ),
+                        (
+                            Expr::Cmp(
+                                Op::UGe,
+                                Box::new(Expr::ConstInt64(1)),
+                                Box::new(Expr::ConstInt64(-1)),
+                            ),
+                            vec![Stmt::FuncCall(
+                                "exit".to_string(),
+                                vec![Expr::ConstInt32(6)],
+                            )],
+                        ),
+                        (
+                            Expr::Not(Box::new(Expr::Cmp(
+                                Op::Lt,
+                                Box::new(Expr::ConstInt32(-1)),
+                                Box::new(Expr::ConstInt32(1)),
+                            ))),
+                            vec![Stmt::FuncCall(
+                                "exit".to_string(),
+                                vec![Expr::ConstInt32(7)],
+                            )],
+                        ),
+                    ]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("ord works!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
@@ -10,6 +10,7 @@ pub fn cond() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("s".to_string(), Type::Str)],
                 false,
+                false,
             ),
             Decl::FwdDecl(
                 "exit".to_string(),
@@ -17,6 +18,7 @@ pub fn cond() -> Vec<Module> {
                 None,
                 vec![("status".to_string(), Type::Int32)],
                 false,
+                true,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -24,6 +26,7 @@ pub fn cond() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![
                     Stmt::Cond(vec![
                         (
@@ -78,3 +81,98 @@ pub fn cond() -> Vec<Module> {
         ],
     }]
 }
+
+fn x_is(value: i32) -> Expr {
+    Expr::Cmp(
+        Op::Eq,
+        Box::new(Expr::VarRef("x".to_string(), Type::Int32, false)),
+        Box::new(Expr::ConstInt32(value)),
+    )
+}
+
+pub fn noreturn() -> Vec<Module> {
+    vec![Module {
+        name: "noreturn".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                true,
+            ),
+            Decl::FuncDecl(
+                "fail".to_string(),
+                Visibility::Private,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                true,
+                vec![
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("failing".to_string())],
+                    ),
+                    Stmt::FuncCall(
+                        "exit".to_string(),
+                        vec![Expr::VarRef("status".to_string(), Type::Int32, false)],
+                    ),
+                ],
+            ),
+            Decl::FuncDecl(
+                "pick".to_string(),
+                Visibility::Private,
+                Some(Type::Int32),
+                vec![("x".to_string(), Type::Int32)],
+                false,
+                false,
+                vec![
+                    Stmt::Cond(vec![
+                        (x_is(1), vec![Stmt::Ret(Some(Expr::ConstInt32(10)))]),
+                        (x_is(2), vec![Stmt::Ret(Some(Expr::ConstInt32(20)))]),
+                    ]),
+                    Stmt::Unreachable,
+                ],
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::Cond(vec![(
+                        Expr::Cmp(
+                            Op::Ne,
+                            Box::new(Expr::FuncCall(
+                                "pick".to_string(),
+                                Type::Int32,
+                                vec![Expr::ConstInt32(2)],
+                            )),
+                            Box::new(Expr::ConstInt32(20)),
+                        ),
+                        vec![Stmt::FuncCall(
+                            "fail".to_string(),
+                            vec![Expr::ConstInt32(1)],
+                        )],
+                    )]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("noreturn works!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
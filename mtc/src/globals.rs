@@ -0,0 +1,267 @@
+use midlang::*;
+
+fn pt() -> Type {
+    Type::Struct("pt".to_string())
+}
+
+fn primes() -> Type {
+    Type::Array(Box::new(Type::Int32), 4)
+}
+
+fn var_ref(name: &str, r#type: Type) -> Box<Expr> {
+    Box::new(Expr::VarRef(name.to_string(), r#type, false))
+}
+
+fn exit_case(lhs: Expr, rhs: Expr, status: i32) -> Case {
+    (
+        Expr::Cmp(Op::Ne, Box::new(lhs), Box::new(rhs)),
+        vec![Stmt::FuncCall(
+            "exit".to_string(),
+            vec![Expr::ConstInt32(status)],
+        )],
+    )
+}
+
+pub fn globals() -> Vec<Module> {
+    vec![Module {
+        name: "globals".to_string(),
+        decls: vec![
+            Decl::StructDecl(
+                "pt".to_string(),
+                vec![
+                    ("x".to_string(), Type::Int32),
+                    ("y".to_string(), Type::Int64),
+                ],
+            ),
+            Decl::Const(
+                "greeting".to_string(),
+                Visibility::Public,
+                vec![],
+                Expr::ConstStr("globals work!".to_string()),
+            ),
+            Decl::Const(
+                "limit".to_string(),
+                Visibility::Private,
+                vec![],
+                Expr::ConstInt32(3),
+            ),
+            Decl::Const(
+                "origin".to_string(),
+                Visibility::Private,
+                vec![],
+                Expr::StructLit(
+                    pt(),
+                    vec![
+                        ("x".to_string(), Expr::ConstInt32(1)),
+                        ("y".to_string(), Expr::ConstInt64(2)),
+                    ],
+                ),
+            ),
+            Decl::Global(
+                "counter".to_string(),
+                Visibility::Public,
+                vec![],
+                Type::Int32,
+                None,
+            ),
+            Decl::Global(
+                "scale".to_string(),
+                Visibility::Private,
+                vec![],
+                Type::Double,
+                Some(Expr::ConstDouble(2.5)),
+            ),
+            Decl::Global(
+                "primes".to_string(),
+                Visibility::Private,
+                vec![],
+                primes(),
+                Some(Expr::ArrayLit(
+                    primes(),
+                    vec![
+                        Expr::ConstInt32(2),
+                        Expr::ConstInt32(3),
+                        Expr::ConstInt32(5),
+                        Expr::ConstInt32(7),
+                    ],
+                )),
+            ),
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "bump".to_string(),
+                Visibility::Private,
+                None,
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::Assign(
+                        "counter".to_string(),
+                        Expr::Arith(
+                            ArithOp::Add,
+                            var_ref("counter", Type::Int32),
+                            Box::new(Expr::ConstInt32(1)),
+                        ),
+                    ),
+                    Stmt::Ret(None),
+                ],
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::While(
+                        Expr::Cmp(
+                            Op::Lt,
+                            var_ref("counter", Type::Int32),
+                            var_ref("limit", Type::Int32),
+                        ),
+                        vec![Stmt::FuncCall("bump".to_string(), vec![])],
+                    ),
+                    Stmt::IndexAssign(
+                        "primes".to_string(),
+                        Expr::ConstInt32(0),
+                        Expr::ConstInt32(11),
+                    ),
+                    Stmt::Cond(vec![
+                        exit_case(*var_ref("counter", Type::Int32), Expr::ConstInt32(3), 1),
+                        exit_case(
+                            Expr::Index(
+                                var_ref("primes", primes()),
+                                Box::new(Expr::ConstInt32(2)),
+                                Type::Int32,
+                            ),
+                            Expr::ConstInt32(5),
+                            2,
+                        ),
+                        exit_case(
+                            Expr::Index(
+                                var_ref("primes", primes()),
+                                Box::new(Expr::ConstInt32(0)),
+                                Type::Int32,
+                            ),
+                            Expr::ConstInt32(11),
+                            3,
+                        ),
+                        exit_case(
+                            Expr::Field(var_ref("origin", pt()), "y".to_string(), Type::Int64),
+                            Expr::ConstInt64(2),
+                            4,
+                        ),
+                        exit_case(*var_ref("scale", Type::Double), Expr::ConstDouble(2.5), 5),
+                    ]),
+                    Stmt::FuncCall("puts".to_string(), vec![*var_ref("greeting", Type::Str)]),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
+
+fn steps() -> Type {
+    Type::Array(Box::new(Type::Int32), 3)
+}
+
+pub fn linkage() -> Vec<Module> {
+    vec![Module {
+        name: "linkage".to_string(),
+        decls: vec![
+            Decl::Const(
+                "steps".to_string(),
+                Visibility::Private,
+                vec![Linkage::Section(".rodata.workflow".to_string(), None)],
+                Expr::ArrayLit(
+                    steps(),
+                    vec![
+                        Expr::ConstInt32(10),
+                        Expr::ConstInt32(20),
+                        Expr::ConstInt32(30),
+                    ],
+                ),
+            ),
+            Decl::Global(
+                "revision".to_string(),
+                Visibility::Public,
+                vec![Linkage::Section(
+                    ".data.workflow".to_string(),
+                    Some("aw".to_string()),
+                )],
+                Type::Int64,
+                Some(Expr::ConstInt64(7)),
+            ),
+            Decl::Global(
+                "visits".to_string(),
+                Visibility::Private,
+                vec![Linkage::Thread],
+                Type::Int32,
+                None,
+            ),
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::Assign(
+                        "visits".to_string(),
+                        Expr::Arith(
+                            ArithOp::Add,
+                            var_ref("visits", Type::Int32),
+                            Box::new(Expr::Index(
+                                var_ref("steps", steps()),
+                                Box::new(Expr::ConstInt32(1)),
+                                Type::Int32,
+                            )),
+                        ),
+                    ),
+                    Stmt::Cond(vec![
+                        exit_case(*var_ref("visits", Type::Int32), Expr::ConstInt32(20), 1),
+                        exit_case(*var_ref("revision", Type::Int64), Expr::ConstInt64(7), 2),
+                    ]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("linkage works!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
@@ -10,6 +10,7 @@ pub fn hello_world() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("s".to_string(), Type::Str)],
                 false,
+                false,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -17,6 +18,7 @@ pub fn hello_world() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![
                     Stmt::VarDecl(
                         "r".to_string(),
@@ -44,6 +46,7 @@ pub fn hello_world2() -> Vec<Module> {
                     Some(Type::Int32),
                     vec![],
                     false,
+                    false,
                 ),
                 Decl::FuncDecl(
                     "main".to_string(),
@@ -51,6 +54,7 @@ pub fn hello_world2() -> Vec<Module> {
                     Some(Type::Int32),
                     vec![],
                     false,
+                    false,
                     vec![
                         Stmt::VarDecl(
                             "r".to_string(),
@@ -70,6 +74,7 @@ pub fn hello_world2() -> Vec<Module> {
                     Some(Type::Int32),
                     vec![("s".to_string(), Type::Str)],
                     false,
+                    false,
                 ),
                 Decl::FuncDecl(
                     "say_hello_world".to_string(),
@@ -77,6 +82,7 @@ pub fn hello_world2() -> Vec<Module> {
                     Some(Type::Int32),
                     vec![],
                     false,
+                    false,
                     vec![
                         Stmt::VarDecl(
                             "r".to_string(),
@@ -93,3 +99,74 @@ pub fn hello_world2() -> Vec<Module> {
         },
     ]
 }
+
+pub fn hello_world3() -> Vec<Module> {
+    vec![
+        Module {
+            name: "hello_world3".to_string(),
+            decls: vec![
+                Decl::Import("hello_world3_sayer".to_string()),
+                Decl::FuncDecl(
+                    "main".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r".to_string(),
+                            Expr::FuncCall("say_hello_world".to_string(), Type::Int32, vec![]),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        },
+        Module {
+            name: "hello_world3_sayer".to_string(),
+            decls: vec![
+                Decl::FwdDecl(
+                    "puts".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                ),
+                Decl::FuncDecl(
+                    "say".to_string(),
+                    Visibility::Private,
+                    Some(Type::Int32),
+                    vec![("s".to_string(), Type::Str)],
+                    false,
+                    false,
+                    vec![Stmt::Ret(Some(Expr::FuncCall(
+                        "puts".to_string(),
+                        Type::Int32,
+                        vec![Expr::VarRef("s".to_string(), Type::Str, false)],
+                    )))],
+                ),
+                Decl::FuncDecl(
+                    "say_hello_world".to_string(),
+                    Visibility::Public,
+                    Some(Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        Stmt::VarDecl(
+                            "r".to_string(),
+                            Expr::FuncCall(
+                                "say".to_string(),
+                                Type::Int32,
+                                vec![Expr::ConstStr("hello world".to_string())],
+                            ),
+                        ),
+                        Stmt::Ret(Some(Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        },
+    ]
+}
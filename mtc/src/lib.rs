@@ -1,13 +1,39 @@
+pub mod arith;
+pub mod arrays;
+pub mod assign;
+pub mod casts;
 pub mod cmp;
 pub mod cond;
+pub mod globals;
 pub mod hello_world;
+pub mod loc;
+pub mod logic;
+pub mod loops;
 pub mod math;
 pub mod not;
+pub mod numbers;
+pub mod shadowing;
+pub mod shared_lib;
 pub mod snippets;
+pub mod strings;
+pub mod structs;
 
+pub use arith::*;
+pub use arrays::*;
+pub use assign::*;
+pub use casts::*;
 pub use cmp::*;
 pub use cond::*;
+pub use globals::*;
 pub use hello_world::*;
+pub use loc::*;
+pub use logic::*;
+pub use loops::*;
 pub use math::*;
 pub use not::*;
+pub use numbers::*;
+pub use shadowing::*;
+pub use shared_lib::*;
 pub use snippets::*;
+pub use strings::*;
+pub use structs::*;
@@ -0,0 +1,53 @@
+use midlang::*;
+
+fn pos(line: Line, column: Option<Column>) -> Loc {
+    Loc::Pos("loc.bpmn".to_string(), line, column)
+}
+
+pub fn loc() -> Vec<Module> {
+    vec![Module {
+        name: "loc".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::Loc(pos(1, None)),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::Loc(pos(2, Some(5))),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("loc works!".to_string())],
+                    ),
+                    Stmt::Loc(Loc::Element("Task_1".to_string())),
+                    Stmt::VarDecl("x".to_string(), Expr::ConstInt32(1)),
+                    Stmt::Loc(pos(4, Some(5))),
+                    Stmt::Cond(vec![(
+                        Expr::Cmp(
+                            Op::Eq,
+                            Box::new(Expr::VarRef("x".to_string(), Type::Int32, false)),
+                            Box::new(Expr::ConstInt32(1)),
+                        ),
+                        vec![
+                            Stmt::Loc(pos(5, Some(9))),
+                            Stmt::Ret(Some(Expr::ConstInt32(0))),
+                        ],
+                    )]),
+                    Stmt::Loc(pos(7, None)),
+                    Stmt::Ret(Some(Expr::ConstInt32(1))),
+                ],
+            ),
+        ],
+    }]
+}
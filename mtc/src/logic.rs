@@ -0,0 +1,128 @@
+use midlang::*;
+
+fn var_ref(name: &str, r#type: Type) -> Box<Expr> {
+    Box::new(Expr::VarRef(name.to_string(), r#type, false))
+}
+
+fn touch(result: bool) -> Box<Expr> {
+    Box::new(Expr::FuncCall(
+        "touch".to_string(),
+        Type::Bool,
+        vec![Expr::ConstBool(result)],
+    ))
+}
+
+fn cmp(op: Op, name: &str, value: i32) -> Box<Expr> {
+    Box::new(Expr::Cmp(
+        op,
+        var_ref(name, Type::Int32),
+        Box::new(Expr::ConstInt32(value)),
+    ))
+}
+
+fn exit_case(expr: Expr, status: i32) -> Case {
+    (
+        expr,
+        vec![Stmt::FuncCall(
+            "exit".to_string(),
+            vec![Expr::ConstInt32(status)],
+        )],
+    )
+}
+
+fn not(name: &str) -> Expr {
+    Expr::Not(var_ref(name, Type::Bool))
+}
+
+pub fn logic() -> Vec<Module> {
+    vec![Module {
+        name: "logic".to_string(),
+        decls: vec![
+            Decl::Global(
+                "calls".to_string(),
+                Visibility::Private,
+                vec![],
+                Type::Int32,
+                None,
+            ),
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "touch".to_string(),
+                Visibility::Private,
+                Some(Type::Bool),
+                vec![("result".to_string(), Type::Bool)],
+                false,
+                false,
+                vec![
+                    Stmt::Assign(
+                        "calls".to_string(),
+                        Expr::Arith(
+                            ArithOp::Add,
+                            var_ref("calls", Type::Int32),
+                            Box::new(Expr::ConstInt32(1)),
+                        ),
+                    ),
+                    Stmt::Ret(Some(*var_ref("result", Type::Bool))),
+                ],
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::VarDecl("x".to_string(), Expr::ConstInt32(1)),
+                    Stmt::VarDecl("y".to_string(), Expr::ConstInt32(2)),
+                    Stmt::VarDecl(
+                        "a".to_string(),
+                        Expr::And(cmp(Op::Eq, "x", 1), cmp(Op::Ne, "y", 3)),
+                    ),
+                    Stmt::VarDecl(
+                        "b".to_string(),
+                        Expr::And(Box::new(Expr::ConstBool(false)), touch(true)),
+                    ),
+                    Stmt::VarDecl(
+                        "c".to_string(),
+                        Expr::Or(Box::new(Expr::ConstBool(true)), touch(false)),
+                    ),
+                    Stmt::Cond(vec![
+                        exit_case(not("a"), 1),
+                        exit_case(*var_ref("b", Type::Bool), 2),
+                        exit_case(not("c"), 3),
+                        exit_case(*cmp(Op::Ne, "calls", 0), 4),
+                    ]),
+                    Stmt::VarDecl(
+                        "d".to_string(),
+                        Expr::Or(touch(false), Box::new(Expr::And(touch(true), touch(true)))),
+                    ),
+                    Stmt::Cond(vec![
+                        exit_case(not("d"), 5),
+                        exit_case(*cmp(Op::Ne, "calls", 3), 6),
+                    ]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("logic works!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
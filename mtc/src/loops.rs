@@ -0,0 +1,62 @@
+use midlang::*;
+
+pub fn loops() -> Vec<Module> {
+    vec![Module {
+        name: "loops".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::While(
+                        Expr::ConstBool(false),
+                        vec![Stmt::FuncCall(
+                            "exit".to_string(),
+                            vec![Expr::ConstInt32(1)],
+                        )],
+                    ),
+                    Stmt::While(
+                        Expr::ConstBool(true),
+                        vec![
+                            Stmt::While(Expr::ConstBool(true), vec![Stmt::Break]),
+                            Stmt::Cond(vec![(
+                                Expr::Cmp(
+                                    Op::Eq,
+                                    Box::new(Expr::ConstInt32(1)),
+                                    Box::new(Expr::ConstInt32(2)),
+                                ),
+                                vec![Stmt::Continue],
+                            )]),
+                            Stmt::FuncCall(
+                                "puts".to_string(),
+                                vec![Expr::ConstStr("loops work!".to_string())],
+                            ),
+                            Stmt::Break,
+                        ],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
@@ -10,6 +10,7 @@ pub fn fabs() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("fmt".to_string(), Type::Str)],
                 true,
+                false,
             ),
             Decl::FwdDecl(
                 "fabs".to_string(),
@@ -17,6 +18,7 @@ pub fn fabs() -> Vec<Module> {
                 Some(Type::Double),
                 vec![("x".to_string(), Type::Double)],
                 false,
+                false,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -24,6 +26,7 @@ pub fn fabs() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![
                     Stmt::FuncCall(
                         "printf".to_string(),
@@ -53,6 +56,7 @@ pub fn frexp() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("fmt".to_string(), Type::Str)],
                 true,
+                false,
             ),
             Decl::FwdDecl(
                 "frexp".to_string(),
@@ -63,6 +67,7 @@ pub fn frexp() -> Vec<Module> {
                     ("exp".to_string(), Type::Ptr(Some(Box::new(Type::Int32)))),
                 ],
                 true,
+                false,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -70,6 +75,7 @@ pub fn frexp() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![
                     Stmt::VarDecl("exp".to_string(), Expr::ConstInt32(0)),
                     Stmt::FuncCall(
@@ -10,6 +10,7 @@ pub fn not() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("s".to_string(), Type::Str)],
                 false,
+                false,
             ),
             Decl::FwdDecl(
                 "exit".to_string(),
@@ -17,6 +18,7 @@ pub fn not() -> Vec<Module> {
                 None,
                 vec![("status".to_string(), Type::Int32)],
                 false,
+                false,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -24,6 +26,7 @@ pub fn not() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![
                     Stmt::Cond(vec![
                         (
@@ -0,0 +1,154 @@
+use midlang::*;
+
+fn arith(op: ArithOp, lhs: Expr, rhs: Expr) -> Expr {
+    Expr::Arith(op, Box::new(lhs), Box::new(rhs))
+}
+
+fn cast(r#type: Type, expr: Expr) -> Expr {
+    Expr::Cast(r#type, Box::new(expr))
+}
+
+fn field(name: &str, r#type: Type) -> Expr {
+    Expr::Field(
+        Box::new(Expr::VarRef(
+            "s".to_string(),
+            Type::Struct("Sample".to_string()),
+            false,
+        )),
+        name.to_string(),
+        r#type,
+    )
+}
+
+pub fn numbers() -> Vec<Module> {
+    let cases = [
+        (
+            arith(ArithOp::Add, Expr::ConstInt8(127), Expr::ConstInt8(1)),
+            Expr::ConstInt8(-128),
+        ),
+        (
+            arith(ArithOp::Sub, Expr::ConstUInt8(0), Expr::ConstUInt8(1)),
+            Expr::ConstUInt8(255),
+        ),
+        (
+            arith(
+                ArithOp::Div,
+                Expr::ConstUInt32(4294967295),
+                Expr::ConstUInt32(2),
+            ),
+            Expr::ConstUInt32(2147483647),
+        ),
+        (
+            Expr::Cmp(
+                Op::Gt,
+                Box::new(Expr::ConstUInt32(4294967295)),
+                Box::new(Expr::ConstUInt32(1)),
+            ),
+            Expr::ConstBool(true),
+        ),
+        (
+            Expr::Bitwise(
+                BitOp::Shr,
+                Box::new(Expr::ConstInt16(-16)),
+                Box::new(Expr::ConstInt16(12)),
+            ),
+            Expr::ConstInt16(15),
+        ),
+        (
+            arith(
+                ArithOp::Mul,
+                Expr::ConstFloat32(1.5),
+                Expr::ConstFloat32(4.0),
+            ),
+            Expr::ConstFloat32(6.0),
+        ),
+        (
+            cast(Type::Int8, Expr::ConstUInt8(200)),
+            Expr::ConstInt8(-56),
+        ),
+        (
+            cast(Type::UInt64, Expr::ConstInt8(-1)),
+            Expr::ConstUInt64(u64::MAX),
+        ),
+        (
+            cast(Type::Double, Expr::ConstFloat32(0.5)),
+            Expr::ConstDouble(0.5),
+        ),
+        (
+            cast(Type::UInt8, Expr::ConstDouble(200.0)),
+            Expr::ConstUInt8(200),
+        ),
+        (field("tag", Type::UInt8), Expr::ConstUInt8(255)),
+        (field("count", Type::Int16), Expr::ConstInt16(-300)),
+        (field("ratio", Type::Float32), Expr::ConstFloat32(0.25)),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (lhs, rhs))| {
+        (
+            Expr::Cmp(Op::Ne, Box::new(lhs), Box::new(rhs)),
+            vec![Stmt::FuncCall(
+                "exit".to_string(),
+                vec![Expr::ConstInt32(i as i32 + 1)],
+            )],
+        )
+    })
+    .collect();
+
+    vec![Module {
+        name: "numbers".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::StructDecl(
+                "Sample".to_string(),
+                vec![
+                    ("tag".to_string(), Type::UInt8),
+                    ("count".to_string(), Type::Int16),
+                    ("ratio".to_string(), Type::Float32),
+                ],
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::VarDecl(
+                        "s".to_string(),
+                        Expr::StructLit(
+                            Type::Struct("Sample".to_string()),
+                            vec![
+                                ("tag".to_string(), Expr::ConstUInt8(255)),
+                                ("count".to_string(), Expr::ConstInt16(-300)),
+                                ("ratio".to_string(), Expr::ConstFloat32(0.25)),
+                            ],
+                        ),
+                    ),
+                    Stmt::Cond(cases),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("numbers work!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
@@ -0,0 +1,92 @@
+use midlang::*;
+
+pub fn shadowing() -> Vec<Module> {
+    vec![Module {
+        name: "shadowing".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::VarDecl("x".to_string(), Expr::ConstInt32(1)),
+                    Stmt::VarDeclMut("i".to_string(), Expr::ConstInt32(0)),
+                    Stmt::Cond(vec![(
+                        Expr::ConstBool(true),
+                        vec![
+                            Stmt::VarDeclMut("x".to_string(), Expr::ConstInt32(2)),
+                            Stmt::Assign(
+                                "x".to_string(),
+                                Expr::Arith(
+                                    ArithOp::Add,
+                                    Box::new(Expr::VarRef("x".to_string(), Type::Int32, false)),
+                                    Box::new(Expr::ConstInt32(1)),
+                                ),
+                            ),
+                        ],
+                    )]),
+                    Stmt::While(
+                        Expr::Cmp(
+                            Op::Lt,
+                            Box::new(Expr::VarRef("i".to_string(), Type::Int32, false)),
+                            Box::new(Expr::ConstInt32(3)),
+                        ),
+                        vec![
+                            Stmt::VarDecl(
+                                "x".to_string(),
+                                Expr::Arith(
+                                    ArithOp::Add,
+                                    Box::new(Expr::VarRef("x".to_string(), Type::Int32, false)),
+                                    Box::new(Expr::ConstInt32(10)),
+                                ),
+                            ),
+                            Stmt::Assign(
+                                "i".to_string(),
+                                Expr::Arith(
+                                    ArithOp::Add,
+                                    Box::new(Expr::VarRef("i".to_string(), Type::Int32, false)),
+                                    Box::new(Expr::ConstInt32(1)),
+                                ),
+                            ),
+                        ],
+                    ),
+                    Stmt::Cond(vec![(
+                        Expr::Cmp(
+                            Op::Ne,
+                            Box::new(Expr::VarRef("x".to_string(), Type::Int32, false)),
+                            Box::new(Expr::ConstInt32(1)),
+                        ),
+                        vec![Stmt::FuncCall(
+                            "exit".to_string(),
+                            vec![Expr::ConstInt32(1)],
+                        )],
+                    )]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("shadowing works!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
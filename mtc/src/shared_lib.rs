@@ -0,0 +1,103 @@
+use midlang::*;
+
+fn puts() -> Decl {
+    Decl::FwdDecl(
+        "puts".to_string(),
+        Visibility::Public,
+        Some(Type::Int32),
+        vec![("s".to_string(), Type::Str)],
+        false,
+        false,
+    )
+}
+
+pub fn shared_lib() -> Vec<Module> {
+    vec![Module {
+        name: "shared_lib".to_string(),
+        decls: vec![
+            puts(),
+            Decl::Global(
+                "bumps".to_string(),
+                Visibility::Public,
+                vec![],
+                Type::Int32,
+                None,
+            ),
+            Decl::FuncDecl(
+                "bump".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::Assign(
+                        "bumps".to_string(),
+                        Expr::Arith(
+                            ArithOp::Add,
+                            Box::new(Expr::VarRef("bumps".to_string(), Type::Int32, false)),
+                            Box::new(Expr::ConstInt32(1)),
+                        ),
+                    ),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("bumped".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::VarRef("bumps".to_string(), Type::Int32, false))),
+                ],
+            ),
+        ],
+    }]
+}
+
+pub fn shared_lib_main() -> Vec<Module> {
+    vec![Module {
+        name: "shared_lib_main".to_string(),
+        decls: vec![
+            puts(),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "bump".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::FuncCall("bump".to_string(), vec![]),
+                    Stmt::Cond(vec![(
+                        Expr::Cmp(
+                            Op::Ne,
+                            Box::new(Expr::FuncCall("bump".to_string(), Type::Int32, vec![])),
+                            Box::new(Expr::ConstInt32(2)),
+                        ),
+                        vec![Stmt::FuncCall(
+                            "exit".to_string(),
+                            vec![Expr::ConstInt32(1)],
+                        )],
+                    )]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("shared lib works!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
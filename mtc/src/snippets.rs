@@ -13,6 +13,7 @@ pub fn func_call_variadic_params_multiple() -> Vec<Module> {
                     ("n".to_string(), Type::Int32),
                 ],
                 true,
+                false,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -20,6 +21,7 @@ pub fn func_call_variadic_params_multiple() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![
                     Stmt::VarDecl(
                         "r1".to_string(),
@@ -62,6 +64,7 @@ pub fn nested_func_call() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("s".to_string(), Type::Str)],
                 false,
+                false,
             ),
             Decl::FwdDecl(
                 "ok".to_string(),
@@ -69,6 +72,7 @@ pub fn nested_func_call() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("n".to_string(), Type::Int32)],
                 false,
+                false,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -76,6 +80,7 @@ pub fn nested_func_call() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![Stmt::Ret(Some(Expr::FuncCall(
                     "ok".to_string(),
                     Type::Int32,
@@ -100,6 +105,7 @@ pub fn func_call_variadic_params_just_one() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![("fmt".to_string(), Type::Str)],
                 true,
+                false,
             ),
             Decl::FuncDecl(
                 "main".to_string(),
@@ -107,6 +113,7 @@ pub fn func_call_variadic_params_just_one() -> Vec<Module> {
                 Some(Type::Int32),
                 vec![],
                 false,
+                false,
                 vec![
                     Stmt::VarDecl(
                         "r1".to_string(),
@@ -143,6 +150,7 @@ pub fn var_ref() -> Vec<Module> {
             Some(Type::Int32),
             vec![],
             false,
+            false,
             vec![
                 Stmt::VarDecl("x".to_string(), Expr::ConstInt32(0)),
                 Stmt::Ret(Some(Expr::VarRef("x".to_string(), Type::Int32, false))),
@@ -160,6 +168,7 @@ pub fn void_main() -> Vec<Module> {
             None,
             vec![],
             false,
+            false,
             vec![Stmt::Ret(None)],
         )],
     }]
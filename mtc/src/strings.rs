@@ -0,0 +1,138 @@
+use midlang::*;
+
+// Each string is compared against its bytes built as an array, so the check does not go through
+// the string pool it is meant to test
+const STRS: [&str; 5] = [
+    "say \"hi\"",
+    "C:\\path\\",
+    "tab\there\r\n",
+    "h\u{e9}llo \u{20ac} \u{1f600}",
+    "nul\0inside",
+];
+
+// A fixed xorshift sequence of strings over ASCII, control chars and multi-byte chars, so the
+// test case and its goldens stay the same from run to run
+fn generated_strs() -> Vec<String> {
+    let alphabet = (1..128u8)
+        .map(char::from)
+        .chain([
+            '\u{e9}',
+            '\u{df}',
+            '\u{20ac}',
+            '\u{80}',
+            '\u{ffff}',
+            '\u{1f600}',
+        ])
+        .collect::<Vec<_>>();
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as usize
+    };
+
+    (0..16)
+        .map(|_| {
+            let len = next() % 16;
+            (0..len)
+                .map(|_| alphabet[next() % alphabet.len()])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn bytes_type(str: &str) -> Type {
+    Type::Array(Box::new(Type::UInt8), str.len() + 1)
+}
+
+fn check_str(i: usize, str: &str) -> Vec<Stmt> {
+    let name = format!("bytes{}", i);
+    let bytes = str
+        .bytes()
+        .chain([0])
+        .map(Expr::ConstUInt8)
+        .collect::<Vec<_>>();
+
+    vec![
+        Stmt::VarDecl(name.to_string(), Expr::ArrayLit(bytes_type(str), bytes)),
+        Stmt::Cond(vec![(
+            Expr::Cmp(
+                Op::Ne,
+                Box::new(Expr::FuncCall(
+                    "memcmp".to_string(),
+                    Type::Int32,
+                    vec![
+                        Expr::ConstStr(str.to_string()),
+                        Expr::VarRef(name, bytes_type(str), true),
+                        Expr::ConstUInt64(str.len() as u64 + 1),
+                    ],
+                )),
+                Box::new(Expr::ConstInt32(0)),
+            ),
+            vec![Stmt::FuncCall(
+                "exit".to_string(),
+                vec![Expr::ConstInt32(i as i32 + 1)],
+            )],
+        )]),
+    ]
+}
+
+pub fn strings() -> Vec<Module> {
+    let mut stmts = STRS
+        .iter()
+        .map(|str| str.to_string())
+        .chain(generated_strs())
+        .enumerate()
+        .flat_map(|(i, str)| check_str(i, &str))
+        .collect::<Vec<_>>();
+
+    stmts.push(Stmt::FuncCall(
+        "puts".to_string(),
+        vec![Expr::ConstStr("strings work!".to_string())],
+    ));
+    stmts.push(Stmt::Ret(Some(Expr::ConstInt32(0))));
+
+    vec![Module {
+        name: "strings".to_string(),
+        decls: vec![
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "memcmp".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![
+                    ("s1".to_string(), Type::Str),
+                    ("s2".to_string(), Type::Ptr(Some(Box::new(Type::UInt8)))),
+                    ("n".to_string(), Type::UInt64),
+                ],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                stmts,
+            ),
+        ],
+    }]
+}
@@ -0,0 +1,227 @@
+use midlang::*;
+
+fn pt() -> Type {
+    Type::Struct("pt".to_string())
+}
+
+fn var_ref(name: &str, r#type: Type) -> Box<Expr> {
+    Box::new(Expr::VarRef(name.to_string(), r#type, false))
+}
+
+fn field(expr: Box<Expr>, name: &str, r#type: Type) -> Box<Expr> {
+    Box::new(Expr::Field(expr, name.to_string(), r#type))
+}
+
+fn exit_case(expr: Expr, status: i32) -> Case {
+    (
+        expr,
+        vec![Stmt::FuncCall(
+            "exit".to_string(),
+            vec![Expr::ConstInt32(status)],
+        )],
+    )
+}
+
+pub fn structs() -> Vec<Module> {
+    vec![Module {
+        name: "structs".to_string(),
+        decls: vec![
+            Decl::StructDecl(
+                "div_t".to_string(),
+                vec![
+                    ("quot".to_string(), Type::Int32),
+                    ("rem".to_string(), Type::Int32),
+                ],
+            ),
+            Decl::StructDecl(
+                "pt".to_string(),
+                vec![
+                    ("x".to_string(), Type::Int32),
+                    ("y".to_string(), Type::Int64),
+                ],
+            ),
+            Decl::StructDecl(
+                "seg".to_string(),
+                vec![
+                    ("a".to_string(), pt()),
+                    ("b".to_string(), pt()),
+                    ("len".to_string(), Type::Double),
+                ],
+            ),
+            Decl::FwdDecl(
+                "puts".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![("s".to_string(), Type::Str)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "exit".to_string(),
+                Visibility::Public,
+                None,
+                vec![("status".to_string(), Type::Int32)],
+                false,
+                false,
+            ),
+            Decl::FwdDecl(
+                "div".to_string(),
+                Visibility::Public,
+                Some(Type::Struct("div_t".to_string())),
+                vec![
+                    ("numer".to_string(), Type::Int32),
+                    ("denom".to_string(), Type::Int32),
+                ],
+                false,
+                false,
+            ),
+            Decl::FuncDecl(
+                "mk_pt".to_string(),
+                Visibility::Private,
+                Some(pt()),
+                vec![
+                    ("x".to_string(), Type::Int32),
+                    ("y".to_string(), Type::Int64),
+                ],
+                false,
+                false,
+                vec![Stmt::Ret(Some(Expr::StructLit(
+                    pt(),
+                    vec![
+                        ("y".to_string(), *var_ref("y", Type::Int64)),
+                        ("x".to_string(), *var_ref("x", Type::Int32)),
+                    ],
+                )))],
+            ),
+            Decl::FuncDecl(
+                "pt_y".to_string(),
+                Visibility::Private,
+                Some(Type::Int64),
+                vec![("p".to_string(), pt())],
+                false,
+                false,
+                vec![Stmt::Ret(Some(*field(
+                    var_ref("p", pt()),
+                    "y",
+                    Type::Int64,
+                )))],
+            ),
+            Decl::FuncDecl(
+                "main".to_string(),
+                Visibility::Public,
+                Some(Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    Stmt::VarDecl(
+                        "q".to_string(),
+                        Expr::FuncCall(
+                            "div".to_string(),
+                            Type::Struct("div_t".to_string()),
+                            vec![Expr::ConstInt32(17), Expr::ConstInt32(5)],
+                        ),
+                    ),
+                    Stmt::VarDecl(
+                        "s".to_string(),
+                        Expr::StructLit(
+                            Type::Struct("seg".to_string()),
+                            vec![
+                                (
+                                    "a".to_string(),
+                                    Expr::FuncCall(
+                                        "mk_pt".to_string(),
+                                        pt(),
+                                        vec![Expr::ConstInt32(1), Expr::ConstInt64(2)],
+                                    ),
+                                ),
+                                (
+                                    "b".to_string(),
+                                    Expr::StructLit(
+                                        pt(),
+                                        vec![
+                                            ("x".to_string(), Expr::ConstInt32(3)),
+                                            ("y".to_string(), Expr::ConstInt64(4)),
+                                        ],
+                                    ),
+                                ),
+                                ("len".to_string(), Expr::ConstDouble(2.5)),
+                            ],
+                        ),
+                    ),
+                    Stmt::VarDecl(
+                        "b".to_string(),
+                        *field(var_ref("s", Type::Struct("seg".to_string())), "b", pt()),
+                    ),
+                    Stmt::Cond(vec![
+                        exit_case(
+                            Expr::Cmp(
+                                Op::Ne,
+                                field(
+                                    var_ref("q", Type::Struct("div_t".to_string())),
+                                    "quot",
+                                    Type::Int32,
+                                ),
+                                Box::new(Expr::ConstInt32(3)),
+                            ),
+                            1,
+                        ),
+                        exit_case(
+                            Expr::Cmp(
+                                Op::Ne,
+                                field(
+                                    var_ref("q", Type::Struct("div_t".to_string())),
+                                    "rem",
+                                    Type::Int32,
+                                ),
+                                Box::new(Expr::ConstInt32(2)),
+                            ),
+                            2,
+                        ),
+                        exit_case(
+                            Expr::Cmp(
+                                Op::Ne,
+                                Box::new(Expr::FuncCall(
+                                    "pt_y".to_string(),
+                                    Type::Int64,
+                                    vec![Expr::VarRef("b".to_string(), pt(), false)],
+                                )),
+                                Box::new(Expr::ConstInt64(4)),
+                            ),
+                            3,
+                        ),
+                        exit_case(
+                            Expr::Cmp(
+                                Op::Ne,
+                                field(
+                                    field(var_ref("s", Type::Struct("seg".to_string())), "a", pt()),
+                                    "x",
+                                    Type::Int32,
+                                ),
+                                Box::new(Expr::ConstInt32(1)),
+                            ),
+                            4,
+                        ),
+                        exit_case(
+                            Expr::Cmp(
+                                Op::Ne,
+                                field(
+                                    var_ref("s", Type::Struct("seg".to_string())),
+                                    "len",
+                                    Type::Double,
+                                ),
+                                Box::new(Expr::ConstDouble(2.5)),
+                            ),
+                            5,
+                        ),
+                    ]),
+                    Stmt::FuncCall(
+                        "puts".to_string(),
+                        vec![Expr::ConstStr("structs work!".to_string())],
+                    ),
+                    Stmt::Ret(Some(Expr::ConstInt32(0))),
+                ],
+            ),
+        ],
+    }]
+}
@@ -10,7 +10,7 @@ const INDENT: &str = "    ";
 
 const RENDER_VALUE_PLAIN: u8 = 0;
 const RENDER_VALUE_TYPES: u8 = 1 << 0;
-const RENDER_VALUE_COPY_LITERALS: u8 = 1 << 1;
+const RENDER_VALUE_COPY: u8 = 1 << 1;
 
 pub fn generate_il(comp_units: &[CompUnit]) -> Result<BuildArtifacts, fmt::Error> {
     let mut build_artifacts = BuildArtifacts::with_capacity(comp_units.len());
@@ -38,21 +38,29 @@ fn decls_il(decls: &[Decl]) -> Result<String, fmt::Error> {
 
 fn append_decl_il(decl: &Decl, il: &mut impl Write) -> fmt::Result {
     match decl {
-        Decl::Data(name, fields) => {
+        Decl::Data(name, linkage, fields) => {
+            for linkage in linkage {
+                write!(il, "{} ", linkage)?;
+            }
+
             write!(il, "data ${} = {{ ", name)?;
 
-            for (i, (r#type, value)) in fields.iter().enumerate() {
+            for (i, field) in fields.iter().enumerate() {
                 if i > 0 {
                     il.write_str(", ")?;
                 }
 
-                write!(il, "{} {}", r#type, value)?;
+                match field {
+                    DataField::Value(r#type, value) => write!(il, "{} {}", r#type, value)?,
+                    DataField::Zero(bytes) => write!(il, "z {}", bytes)?,
+                }
             }
 
             il.write_str(" }\n")?;
         }
+        Decl::DbgFile(file) => writeln!(il, "dbgfile \"{}\"", file)?,
         Decl::FuncDecl(name, linkage, r#type, args, variadic, stmts) => {
-            if let Some(linkage) = linkage {
+            for linkage in linkage {
                 write!(il, "{} ", linkage)?;
             }
 
@@ -72,6 +80,19 @@ fn append_decl_il(decl: &Decl, il: &mut impl Write) -> fmt::Result {
 
             il.write_str("}\n")?;
         }
+        Decl::Type(name, types) => {
+            write!(il, "type :{} = {{ ", name)?;
+
+            for (i, r#type) in types.iter().enumerate() {
+                if i > 0 {
+                    il.write_str(", ")?;
+                }
+
+                write!(il, "{}", r#type.store_type())?;
+            }
+
+            il.write_str(" }\n")?;
+        }
     }
 
     Ok(())
@@ -120,7 +141,19 @@ fn append_func_call_il(
 fn append_stmts_il(stmts: &[Stmt], il: &mut impl Write) -> fmt::Result {
     for stmt in stmts {
         match stmt {
+            Stmt::Blit(src, dest, bytes) => {
+                write!(il, "{}blit ", INDENT)?;
+                append_value_il(src, RENDER_VALUE_PLAIN, il)?;
+                il.write_str(", ")?;
+                append_value_il(dest, RENDER_VALUE_PLAIN, il)?;
+                write!(il, ", {}", bytes)?;
+            }
+            Stmt::DbgLoc(line, None) => write!(il, "{}dbgloc {}", INDENT, line)?,
+            Stmt::DbgLoc(line, Some(column)) => {
+                write!(il, "{}dbgloc {}, {}", INDENT, line, column)?
+            }
             Stmt::FuncCall(name, values) => append_func_call_il(name, values, true, il)?,
+            Stmt::Hlt => write!(il, "{}hlt", INDENT)?,
             Stmt::Jmp(lbl) => write!(il, "{}jmp @{}", INDENT, lbl)?,
             Stmt::Jnz(value, true_lbl, false_lbl) => {
                 write!(il, "{}jnz ", INDENT)?;
@@ -134,14 +167,21 @@ fn append_stmts_il(stmts: &[Stmt], il: &mut impl Write) -> fmt::Result {
             }
             Stmt::Ret(None) => write!(il, "{}ret", INDENT)?,
             Stmt::Store(r#type, src, dest) => {
-                write!(il, "{}store{} ", INDENT, r#type)?;
+                write!(il, "{}store{} ", INDENT, r#type.store_type())?;
                 append_value_il(src, RENDER_VALUE_PLAIN, il)?;
                 il.write_str(", ")?;
                 append_value_il(dest, RENDER_VALUE_PLAIN, il)?;
             }
             Stmt::VarDecl(name, scope, expr) => {
-                write!(il, "{}{}{} ={} ", INDENT, scope, name, expr.r#type())?;
-                append_expr_il(expr, RENDER_VALUE_COPY_LITERALS, il)?;
+                // Only calls can produce aggregates, otherwise they are referenced by address
+                let r#type = match (expr, expr.r#type()) {
+                    (Expr::FuncCall(_, _, _), r#type) => r#type,
+                    (_, Type::Agg(_)) => Type::L,
+                    (_, r#type) => r#type,
+                };
+
+                write!(il, "{}{}{} ={} ", INDENT, scope, name, r#type)?;
+                append_expr_il(expr, RENDER_VALUE_COPY, il)?;
             }
         }
 
@@ -153,30 +193,59 @@ fn append_stmts_il(stmts: &[Stmt], il: &mut impl Write) -> fmt::Result {
 
 fn append_expr_il(expr: &Expr, value_render_flags: u8, il: &mut impl Write) -> fmt::Result {
     match expr {
+        Expr::Add(lhs, rhs) => append_binary_expr_il("add", lhs, rhs, il)?,
         Expr::Alloc8(bytes) => write!(il, "alloc8 {}", bytes)?,
+        Expr::And(lhs, rhs) => append_binary_expr_il("and", lhs, rhs, il)?,
         Expr::Cmp(op, lhs, rhs) => {
             write!(il, "c{}{} ", op, lhs.r#type())?;
             append_value_il(lhs, RENDER_VALUE_PLAIN, il)?;
             il.write_str(", ")?;
             append_value_il(rhs, RENDER_VALUE_PLAIN, il)?;
         }
+        Expr::Conv(conv, _, value) => {
+            write!(il, "{} ", conv)?;
+            append_value_il(value, RENDER_VALUE_PLAIN, il)?;
+        }
+        Expr::Div(lhs, rhs) => append_binary_expr_il("div", lhs, rhs, il)?,
         Expr::Load(_, r#type, value) => {
             write!(il, "load{} ", r#type)?;
-            append_value_il(value, value_render_flags, il)?;
+            append_value_il(value, RENDER_VALUE_PLAIN, il)?;
         }
-        Expr::Sub(value1, value2) => {
-            il.write_str("sub ")?;
-            append_value_il(value1, RENDER_VALUE_PLAIN, il)?;
-            il.write_str(", ")?;
-            append_value_il(value2, RENDER_VALUE_PLAIN, il)?;
+        Expr::Mul(lhs, rhs) => append_binary_expr_il("mul", lhs, rhs, il)?,
+        Expr::Neg(value) => {
+            il.write_str("neg ")?;
+            append_value_il(value, RENDER_VALUE_PLAIN, il)?;
         }
+        Expr::Or(lhs, rhs) => append_binary_expr_il("or", lhs, rhs, il)?,
+        Expr::Rem(lhs, rhs) => append_binary_expr_il("rem", lhs, rhs, il)?,
+        Expr::Sar(lhs, rhs) => append_binary_expr_il("sar", lhs, rhs, il)?,
+        Expr::Shl(lhs, rhs) => append_binary_expr_il("shl", lhs, rhs, il)?,
+        Expr::Shr(lhs, rhs) => append_binary_expr_il("shr", lhs, rhs, il)?,
+        Expr::Sub(lhs, rhs) => append_binary_expr_il("sub", lhs, rhs, il)?,
+        Expr::UDiv(lhs, rhs) => append_binary_expr_il("udiv", lhs, rhs, il)?,
+        Expr::URem(lhs, rhs) => append_binary_expr_il("urem", lhs, rhs, il)?,
         Expr::Value(value) => append_value_il(value, value_render_flags, il)?,
         Expr::FuncCall(name, _, values) => append_func_call_il(name, values, false, il)?,
+        Expr::Xor(lhs, rhs) => append_binary_expr_il("xor", lhs, rhs, il)?,
     }
 
     Ok(())
 }
 
+fn append_binary_expr_il(
+    instr: &str,
+    lhs: &Value,
+    rhs: &Value,
+    il: &mut impl Write,
+) -> fmt::Result {
+    write!(il, "{} ", instr)?;
+    append_value_il(lhs, RENDER_VALUE_PLAIN, il)?;
+    il.write_str(", ")?;
+    append_value_il(rhs, RENDER_VALUE_PLAIN, il)?;
+
+    Ok(())
+}
+
 fn append_value_il(value: &Value, render_flags: u8, il: &mut impl Write) -> fmt::Result {
     if render_flags & RENDER_VALUE_TYPES != 0 {
         write!(il, "{} ", value.r#type())?;
@@ -184,27 +253,40 @@ fn append_value_il(value: &Value, render_flags: u8, il: &mut impl Write) -> fmt:
 
     match value {
         Value::ConstD(v) => {
-            if render_flags & RENDER_VALUE_COPY_LITERALS != 0 {
+            if render_flags & RENDER_VALUE_COPY != 0 {
                 il.write_str("copy ")?;
             }
 
-            write!(il, "d_{}", v)?;
+            il.write_str(&double_lit(*v))?;
         }
         Value::ConstL(v) => {
-            if render_flags & RENDER_VALUE_COPY_LITERALS != 0 {
+            if render_flags & RENDER_VALUE_COPY != 0 {
                 il.write_str("copy ")?;
             }
 
             write!(il, "{}", v)?;
         }
+        Value::ConstS(v) => {
+            if render_flags & RENDER_VALUE_COPY != 0 {
+                il.write_str("copy ")?;
+            }
+
+            il.write_str(&single_lit(*v))?;
+        }
         Value::ConstW(v) => {
-            if render_flags & RENDER_VALUE_COPY_LITERALS != 0 {
+            if render_flags & RENDER_VALUE_COPY != 0 {
                 il.write_str("copy ")?;
             }
 
             write!(il, "{}", v)?;
         }
-        Value::VarRef(name, _, scope) => write!(il, "{}{}", scope, name)?,
+        Value::VarRef(name, _, scope) => {
+            if render_flags & RENDER_VALUE_COPY != 0 {
+                il.write_str("copy ")?;
+            }
+
+            write!(il, "{}{}", scope, name)?;
+        }
     }
 
     Ok(())
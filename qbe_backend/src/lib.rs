@@ -35,13 +35,14 @@ impl compiler::Backend for Backend<'_> {
     fn generate_build_artifacts(
         &self,
         modules: &[m::Module],
+        build_kind: compiler::BuildKind,
         ninja_writer: &mut Ninja,
     ) -> compiler::BackendResult {
         let comp_units = lower(modules);
         let build_artifacts = generate_il(&comp_units)?;
 
         set_link_flags_var(self.libraries, self.library_paths, ninja_writer);
-        configure_ninja_build(&build_artifacts, self.output, ninja_writer);
+        configure_ninja_build(&build_artifacts, build_kind, self.output, ninja_writer);
 
         Ok(build_artifacts)
     }
@@ -65,12 +66,12 @@ fn set_link_flags_var(libraries: &[String], library_paths: &[String], ninja_writ
 
 fn configure_ninja_build(
     build_artifacts: &compiler::BuildArtifacts,
+    build_kind: compiler::BuildKind,
     output: &String,
     ninja_writer: &mut Ninja,
 ) {
     let qbe = ninja_writer.rule("qbe", "qbe -o $out $in");
     let cc = ninja_writer.rule("cc", "cc -o $out -c $in");
-    let link = ninja_writer.rule("link", "cc -o $out $in $link_flags");
     let mut objs = Vec::<String>::with_capacity(build_artifacts.len());
 
     for (il, _) in build_artifacts {
@@ -82,7 +83,24 @@ fn configure_ninja_build(
         objs.push(obj);
     }
 
-    link.build([&output]).with(&objs);
+    // Static libs are archives of the objects as they are, libraries they use are linked by
+    // whatever links the archive. QBE addresses data rip relative, not through the GOT, so a
+    // shared lib only links when it binds its own symbols and -fPIC cannot change the assembly
+    // QBE already wrote. Executables reach the data of such a lib through its funcs
+    let output_rule = match build_kind {
+        compiler::BuildKind::Executable => ninja_writer.rule("link", "cc -o $out $in $link_flags"),
+        compiler::BuildKind::StaticLib => ninja_writer.rule("ar", "ar rcs $out $in"),
+        compiler::BuildKind::SharedLib => ninja_writer.rule(
+            "link_shared",
+            "cc -shared -Wl,-Bsymbolic -o $out $in $link_flags",
+        ),
+        compiler::BuildKind::Object => {
+            ninja_writer.defaults(&objs);
+            return;
+        }
+    };
+
+    output_rule.build([&output]).with(&objs);
     ninja_writer.defaults([&output]);
 }
 
@@ -109,7 +127,11 @@ mod tests {
         ninja_writer: &mut Ninja,
     ) -> compiler::BackendResult {
         let output = "a.out".to_string();
-        new(&vec![], &vec![], &output).generate_build_artifacts(modules, ninja_writer)
+        new(&vec![], &vec![], &output).generate_build_artifacts(
+            modules,
+            compiler::BuildKind::Executable,
+            ninja_writer,
+        )
     }
 
     #[test]
@@ -177,6 +199,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn hello_world3() -> TestResult {
+        let modules = mtc::hello_world3();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 2);
+        assert_eq!(ba[0].0, "hello_world3.il");
+        assert_eq!(ba[1].0, "hello_world3_sayer.il");
+
+        {
+            let path = Path::new(env!("TEST_CASES_DIR"))
+                .join("qbe")
+                .join("hello_world3.il");
+            let expected_il = read_to_string(&path)?;
+
+            assert_eq!(ba[0].1, expected_il);
+        }
+
+        {
+            let path = Path::new(env!("TEST_CASES_DIR"))
+                .join("qbe")
+                .join("hello_world3_sayer.il");
+            let expected_il = read_to_string(&path)?;
+
+            assert_eq!(ba[1].1, expected_il);
+        }
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("hello_world3.il"));
+        assert!(ninja_build.contains("hello_world3.s"));
+        assert!(ninja_build.contains("hello_world3.o"));
+        assert!(ninja_build.contains("hello_world3_sayer.il"));
+        assert!(ninja_build.contains("hello_world3_sayer.s"));
+        assert!(ninja_build.contains("hello_world3_sayer.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
     #[test]
     fn fabs() -> TestResult {
         let modules = mtc::fabs();
@@ -297,4 +359,916 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn add() -> TestResult {
+        let modules = mtc::add();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "add.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR")).join("qbe").join("add.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("add.il"));
+        assert!(ninja_build.contains("add.s"));
+        assert!(ninja_build.contains("add.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sub() -> TestResult {
+        let modules = mtc::sub();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "sub.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR")).join("qbe").join("sub.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("sub.il"));
+        assert!(ninja_build.contains("sub.s"));
+        assert!(ninja_build.contains("sub.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mul() -> TestResult {
+        let modules = mtc::mul();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "mul.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR")).join("qbe").join("mul.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("mul.il"));
+        assert!(ninja_build.contains("mul.s"));
+        assert!(ninja_build.contains("mul.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn div() -> TestResult {
+        let modules = mtc::div();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "div.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR")).join("qbe").join("div.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("div.il"));
+        assert!(ninja_build.contains("div.s"));
+        assert!(ninja_build.contains("div.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rem() -> TestResult {
+        let modules = mtc::rem();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "rem.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR")).join("qbe").join("rem.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("rem.il"));
+        assert!(ninja_build.contains("rem.s"));
+        assert!(ninja_build.contains("rem.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn neg() -> TestResult {
+        let modules = mtc::neg();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "neg.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR")).join("qbe").join("neg.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("neg.il"));
+        assert!(ninja_build.contains("neg.s"));
+        assert!(ninja_build.contains("neg.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ord() -> TestResult {
+        let modules = mtc::ord();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "ord.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR")).join("qbe").join("ord.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("ord.il"));
+        assert!(ninja_build.contains("ord.s"));
+        assert!(ninja_build.contains("ord.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn loops() -> TestResult {
+        let modules = mtc::loops();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "loops.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("loops.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("loops.il"));
+        assert!(ninja_build.contains("loops.s"));
+        assert!(ninja_build.contains("loops.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn assign() -> TestResult {
+        let modules = mtc::assign();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "assign.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("assign.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("assign.il"));
+        assert!(ninja_build.contains("assign.s"));
+        assert!(ninja_build.contains("assign.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn structs() -> TestResult {
+        let modules = mtc::structs();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "structs.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("structs.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("structs.il"));
+        assert!(ninja_build.contains("structs.s"));
+        assert!(ninja_build.contains("structs.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn arrays() -> TestResult {
+        let modules = mtc::arrays();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "arrays.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("arrays.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("arrays.il"));
+        assert!(ninja_build.contains("arrays.s"));
+        assert!(ninja_build.contains("arrays.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn globals() -> TestResult {
+        let modules = mtc::globals();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "globals.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("globals.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("globals.il"));
+        assert!(ninja_build.contains("globals.s"));
+        assert!(ninja_build.contains("globals.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn casts() -> TestResult {
+        let modules = mtc::casts();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "casts.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("casts.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("casts.il"));
+        assert!(ninja_build.contains("casts.s"));
+        assert!(ninja_build.contains("casts.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn logic() -> TestResult {
+        let modules = mtc::logic();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "logic.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("logic.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("logic.il"));
+        assert!(ninja_build.contains("logic.s"));
+        assert!(ninja_build.contains("logic.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bitwise() -> TestResult {
+        let modules = mtc::bitwise();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "bitwise.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("bitwise.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("bitwise.il"));
+        assert!(ninja_build.contains("bitwise.s"));
+        assert!(ninja_build.contains("bitwise.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn numbers() -> TestResult {
+        let modules = mtc::numbers();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "numbers.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("numbers.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("numbers.il"));
+        assert!(ninja_build.contains("numbers.s"));
+        assert!(ninja_build.contains("numbers.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn strings() -> TestResult {
+        let modules = mtc::strings();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "strings.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("strings.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        Ok(())
+    }
+
+    // Declares every constant both as global data and as a local so both rendering paths are
+    // covered, data is emitted before the function in declaration order
+    fn float_module(consts: impl Fn() -> Vec<m::Expr>) -> Vec<m::Module> {
+        let mut decls = consts()
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let r#type = match c.r#type() {
+                    m::Type::Float32 => m::Type::Float32,
+                    _ => m::Type::Double,
+                };
+                m::Decl::Global(
+                    format!("g{}", i),
+                    m::Visibility::Private,
+                    vec![],
+                    r#type,
+                    Some(c),
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut stmts = consts()
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| m::Stmt::VarDecl(format!("x{}", i), c))
+            .collect::<Vec<_>>();
+        stmts.push(m::Stmt::Ret(Some(m::Expr::ConstInt32(0))));
+
+        decls.push(m::Decl::FuncDecl(
+            "main".to_string(),
+            m::Visibility::Public,
+            Some(m::Type::Int32),
+            vec![],
+            false,
+            false,
+            stmts,
+        ));
+
+        vec![m::Module {
+            name: "floats".to_string(),
+            decls,
+        }]
+    }
+
+    fn float_lits<'a>(il: &'a str, prefix: &str) -> Vec<&'a str> {
+        il.split([' ', ',', '\n'])
+            .filter_map(|t| t.strip_prefix(prefix))
+            .collect()
+    }
+
+    const DOUBLE_EDGES: [f64; 12] = [
+        0.0,
+        -0.0,
+        0.1,
+        -2.75,
+        1e15,
+        1e16,
+        1e308,
+        -1e308,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        2.225e-309,
+        5e-324,
+    ];
+
+    const SINGLE_EDGES: [f32; 10] = [
+        0.0,
+        -0.0,
+        0.1,
+        1e16,
+        3e38,
+        f32::MAX,
+        f32::MIN_POSITIVE,
+        1e-40,
+        -1e-45,
+        1e-5,
+    ];
+
+    #[test]
+    fn double_lits() -> TestResult {
+        let modules = float_module(|| DOUBLE_EDGES.map(m::Expr::ConstDouble).into());
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        let lits = float_lits(&ba[0].1, "d_");
+        assert_eq!(lits.len(), DOUBLE_EDGES.len() * 2);
+
+        for (lit, d) in lits
+            .iter()
+            .zip(DOUBLE_EDGES.iter().chain(DOUBLE_EDGES.iter()))
+        {
+            assert!(lit.len() < 24, "{}", lit);
+            assert_eq!(lit.parse::<f64>()?.to_bits(), d.to_bits(), "{}", lit);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn single_lits() -> TestResult {
+        let modules = float_module(|| SINGLE_EDGES.map(m::Expr::ConstFloat32).into());
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        let lits = float_lits(&ba[0].1, "s_");
+        assert_eq!(lits.len(), SINGLE_EDGES.len() * 2);
+
+        for (lit, s) in lits
+            .iter()
+            .zip(SINGLE_EDGES.iter().chain(SINGLE_EDGES.iter()))
+        {
+            assert!(lit.len() < 24, "{}", lit);
+            assert_eq!(lit.parse::<f32>()?.to_bits(), s.to_bits(), "{}", lit);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite_float_lits() -> TestResult {
+        let modules = float_module(|| {
+            vec![
+                m::Expr::ConstDouble(f64::INFINITY),
+                m::Expr::ConstDouble(f64::NAN),
+                m::Expr::ConstFloat32(f32::NEG_INFINITY),
+            ]
+        });
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        let il = &ba[0].1;
+        let inf = f64::INFINITY.to_bits() as i64;
+        let nan = f64::NAN.to_bits() as i64;
+        let neg_inf = f32::NEG_INFINITY.to_bits() as i32;
+
+        assert!(il.contains(&format!("data $g0 = {{ l {} }}", inf)));
+        assert!(il.contains(&format!("data $g1 = {{ l {} }}", nan)));
+        assert!(il.contains(&format!("data $g2 = {{ w {} }}", neg_inf)));
+        assert!(il.contains(&format!("=d cast {}\n", inf)));
+        assert!(il.contains(&format!("=d cast {}\n", nan)));
+        assert!(il.contains(&format!("=s cast {}\n", neg_inf)));
+        assert!(float_lits(il, "d_").is_empty());
+        assert!(float_lits(il, "s_").is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn linkage() -> TestResult {
+        let modules = mtc::linkage();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "linkage.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("linkage.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("linkage.il"));
+        assert!(ninja_build.contains("linkage.s"));
+        assert!(ninja_build.contains("linkage.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn noreturn() -> TestResult {
+        let modules = mtc::noreturn();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "noreturn.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("noreturn.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("noreturn.il"));
+        assert!(ninja_build.contains("noreturn.s"));
+        assert!(ninja_build.contains("noreturn.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn loc() -> TestResult {
+        let modules = mtc::loc();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "loc.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR")).join("qbe").join("loc.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("loc.il"));
+        assert!(ninja_build.contains("loc.s"));
+        assert!(ninja_build.contains("loc.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shadowing() -> TestResult {
+        let modules = mtc::shadowing();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "shadowing.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("shadowing.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        let ninja_build = ninja_writer.to_string();
+        assert!(ninja_build.contains("shadowing.il"));
+        assert!(ninja_build.contains("shadowing.s"));
+        assert!(ninja_build.contains("shadowing.o"));
+        assert!(ninja_build.contains("a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shared_lib() -> TestResult {
+        let modules = mtc::shared_lib();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "shared_lib.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("shared_lib.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        Ok(())
+    }
+
+    #[test]
+    fn shared_lib_main() -> TestResult {
+        let modules = mtc::shared_lib_main();
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        assert_eq!(ba.len(), 1);
+        assert_eq!(ba[0].0, "shared_lib_main.il");
+
+        let path = Path::new(env!("TEST_CASES_DIR"))
+            .join("qbe")
+            .join("shared_lib_main.il");
+        let expected_il = read_to_string(&path)?;
+
+        assert_eq!(ba[0].1, expected_il);
+
+        Ok(())
+    }
+
+    #[test]
+    fn allocs_in_start_block() -> TestResult {
+        let i = || Box::new(m::Expr::VarRef("i".to_string(), m::Type::Int32, false));
+        let pt = || m::Type::Struct("pt".to_string());
+        let modules = [m::Module {
+            name: "allocs".to_string(),
+            decls: vec![
+                m::Decl::StructDecl("pt".to_string(), vec![("x".to_string(), m::Type::Int32)]),
+                m::Decl::FuncDecl(
+                    "main".to_string(),
+                    m::Visibility::Public,
+                    Some(m::Type::Int32),
+                    vec![],
+                    false,
+                    false,
+                    vec![
+                        m::Stmt::VarDeclMut("i".to_string(), m::Expr::ConstInt32(0)),
+                        m::Stmt::While(
+                            m::Expr::Cmp(m::Op::Lt, i(), Box::new(m::Expr::ConstInt32(3))),
+                            vec![
+                                m::Stmt::VarDecl(
+                                    "p".to_string(),
+                                    m::Expr::StructLit(pt(), vec![("x".to_string(), *i())]),
+                                ),
+                                m::Stmt::VarDecl(
+                                    "q".to_string(),
+                                    m::Expr::VarRef("p".to_string(), pt(), false),
+                                ),
+                                m::Stmt::VarDecl(
+                                    "a".to_string(),
+                                    m::Expr::ArrayLit(
+                                        m::Type::Array(Box::new(m::Type::Int32), 2),
+                                        vec![*i(), *i()],
+                                    ),
+                                ),
+                                m::Stmt::FuncCall(
+                                    "touch".to_string(),
+                                    vec![m::Expr::VarRef("i".to_string(), m::Type::Int32, true)],
+                                ),
+                                m::Stmt::Assign(
+                                    "i".to_string(),
+                                    m::Expr::Arith(
+                                        m::ArithOp::Add,
+                                        i(),
+                                        Box::new(m::Expr::ConstInt32(1)),
+                                    ),
+                                ),
+                            ],
+                        ),
+                        m::Stmt::Ret(Some(m::Expr::ConstInt32(0))),
+                    ],
+                ),
+            ],
+        }];
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        let lines = ba[0].1.lines().collect::<Vec<_>>();
+        let body = lines
+            .iter()
+            .position(|line| line.starts_with("@..while"))
+            .expect("Expected a while loop");
+        let allocs = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.contains("alloc8"))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        // The struct literal, the copy of it, the array literal and the byref tmp
+        assert_eq!(allocs.len(), 4);
+        assert!(allocs.iter().all(|&i| i < body));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sub_word_array_layout() -> TestResult {
+        let bytes = m::Type::Array(Box::new(m::Type::UInt8), 3);
+        let modules = [m::Module {
+            name: "bytes".to_string(),
+            decls: vec![m::Decl::FuncDecl(
+                "main".to_string(),
+                m::Visibility::Public,
+                Some(m::Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    m::Stmt::VarDecl(
+                        "a".to_string(),
+                        m::Expr::ArrayLit(
+                            bytes,
+                            vec![
+                                m::Expr::ConstUInt8(1),
+                                m::Expr::ConstUInt8(2),
+                                m::Expr::ConstUInt8(3),
+                            ],
+                        ),
+                    ),
+                    m::Stmt::Ret(Some(m::Expr::ConstInt32(0))),
+                ],
+            )],
+        }];
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        let il = &ba[0].1;
+
+        assert!(il.contains("alloc8 3\n"));
+        assert!(il.contains(", 1\n    storeb 2,"));
+        assert!(il.contains(", 2\n    storeb 3,"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sibling_var_decls() -> TestResult {
+        let modules = [m::Module {
+            name: "siblings".to_string(),
+            decls: vec![m::Decl::FuncDecl(
+                "main".to_string(),
+                m::Visibility::Public,
+                Some(m::Type::Int32),
+                vec![],
+                false,
+                false,
+                vec![
+                    m::Stmt::Cond(vec![
+                        (
+                            m::Expr::ConstBool(false),
+                            vec![m::Stmt::VarDecl("x".to_string(), m::Expr::ConstInt32(1))],
+                        ),
+                        (
+                            m::Expr::ConstBool(true),
+                            vec![m::Stmt::VarDecl("x".to_string(), m::Expr::ConstDouble(1.0))],
+                        ),
+                    ]),
+                    m::Stmt::VarDecl("x".to_string(), m::Expr::ConstInt64(1)),
+                    m::Stmt::Ret(Some(m::Expr::ConstInt32(0))),
+                ],
+            )],
+        }];
+
+        let mut ninja_writer = Ninja::new();
+        let ba = generate_build_artifacts(&modules, &mut ninja_writer)?;
+        let temps = ba[0]
+            .1
+            .lines()
+            .filter_map(|line| line.trim().split_once(" ="))
+            .filter(|(temp, _)| *temp == "%x" || temp.starts_with("%..x.."))
+            .map(|(temp, _)| temp)
+            .collect::<Vec<_>>();
+
+        assert_eq!(temps.len(), 3);
+        assert!(temps
+            .iter()
+            .all(|temp| temps.iter().filter(|t| *t == temp).count() == 1));
+
+        Ok(())
+    }
+
+    fn ninja_build(
+        build_kind: compiler::BuildKind,
+        output: &str,
+    ) -> Result<String, Box<dyn Error>> {
+        let modules = mtc::hello_world2();
+        let output = output.to_string();
+
+        let mut ninja_writer = Ninja::new();
+        new(&vec![], &vec![], &output).generate_build_artifacts(
+            &modules,
+            build_kind,
+            &mut ninja_writer,
+        )?;
+
+        Ok(ninja_writer.to_string())
+    }
+
+    #[test]
+    fn build_executable() -> TestResult {
+        let ninja_build = ninja_build(compiler::BuildKind::Executable, "a.out")?;
+
+        assert!(ninja_build.contains("command = cc -o $out $in $link_flags"));
+        assert!(ninja_build.contains("build a.out: link hello_world2.o hello_world2_sayer.o"));
+        assert!(ninja_build.contains("default a.out"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_static_lib() -> TestResult {
+        let ninja_build = ninja_build(compiler::BuildKind::StaticLib, "libsayer.a")?;
+
+        assert!(ninja_build.contains("command = ar rcs $out $in"));
+        assert!(ninja_build.contains("build libsayer.a: ar hello_world2.o hello_world2_sayer.o"));
+        assert!(ninja_build.contains("default libsayer.a"));
+        assert!(!ninja_build.contains("rule link"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_shared_lib() -> TestResult {
+        let ninja_build = ninja_build(compiler::BuildKind::SharedLib, "libsayer.so")?;
+
+        assert!(ninja_build.contains("command = cc -shared -Wl,-Bsymbolic -o $out $in $link_flags"));
+        assert!(ninja_build
+            .contains("build libsayer.so: link_shared hello_world2.o hello_world2_sayer.o"));
+        assert!(ninja_build.contains("default libsayer.so"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_object() -> TestResult {
+        let ninja_build = ninja_build(compiler::BuildKind::Object, "a.out")?;
+
+        assert!(ninja_build.contains("default hello_world2.o hello_world2_sayer.o"));
+        assert!(!ninja_build.contains("a.out"));
+        assert!(!ninja_build.contains("rule link"));
+
+        Ok(())
+    }
 }
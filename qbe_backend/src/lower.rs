@@ -1,7 +1,7 @@
 use midlang as m;
 
 use crate::lower_lang::*;
-use crate::lowering_context::LoweringCtx;
+use crate::lowering_context::{LoweringCtx, StructLayout};
 
 pub fn lower(modules: &[m::Module]) -> Vec<CompUnit> {
     modules
@@ -9,7 +9,7 @@ pub fn lower(modules: &[m::Module]) -> Vec<CompUnit> {
         .map(|m| {
             let lowered_decls = {
                 let mut ctx = LoweringCtx::new(&m.name);
-                let mut lowered_decls = lower_decls(&m.decls, &mut ctx);
+                let mut lowered_decls = lower_decls(&m.decls, modules, &mut ctx);
                 let mut vec = Vec::<Decl>::with_capacity(ctx.decls_len() + lowered_decls.len());
 
                 vec.append(&mut ctx.decls());
@@ -26,27 +26,230 @@ pub fn lower(modules: &[m::Module]) -> Vec<CompUnit> {
         .collect()
 }
 
-fn lower_decls(decls: &[m::Decl], ctx: &mut LoweringCtx) -> Vec<Decl> {
-    decls
-        .iter()
-        .filter_map(|d| match d {
-            m::Decl::FuncDecl(name, visibility, r#type, args, variadic, m_stmts) => {
+fn lower_decls(decls: &[m::Decl], modules: &[m::Module], ctx: &mut LoweringCtx) -> Vec<Decl> {
+    let mut lowered = Vec::<Decl>::with_capacity(decls.len());
+
+    for (loc, decl) in m::located(decls) {
+        if let Some(m::Loc::Pos(file, _, _)) = loc {
+            if ctx.set_dbg_file(file) {
+                lowered.push(Decl::DbgFile(file.to_string()));
+            }
+        }
+
+        match decl {
+            m::Decl::Const(name, visibility, linkage, expr)
+            | m::Decl::Global(name, visibility, linkage, _, Some(expr)) => {
+                let mut fields = Vec::<DataField>::new();
+                lower_data_fields(expr, &mut fields, ctx);
+                ctx.add_global(name, is_aggregate(expr.r#type()), data_scope(linkage));
+
+                lowered.push(Decl::Data(
+                    name.to_string(),
+                    lower_linkage(visibility, linkage),
+                    fields,
+                ));
+            }
+            m::Decl::Global(name, visibility, linkage, r#type, None) => {
+                let (size, _) = type_size_and_align(r#type, ctx);
+                ctx.add_global(name, is_aggregate(r#type), data_scope(linkage));
+
+                lowered.push(Decl::Data(
+                    name.to_string(),
+                    lower_linkage(visibility, linkage),
+                    vec![DataField::Zero(size)],
+                ));
+            }
+            m::Decl::FuncDecl(name, visibility, r#type, args, variadic, noreturn, m_stmts) => {
+                if *noreturn {
+                    ctx.add_noreturn(name);
+                }
+
+                // dbgfile cannot appear inside a function so the file of its dbglocs is set ahead
+                if let Some(file) = first_loc_file(m_stmts) {
+                    if ctx.set_dbg_file(file) {
+                        lowered.push(Decl::DbgFile(file.to_string()));
+                    }
+                }
+
                 let mut stmts = Vec::<Stmt>::with_capacity(m_stmts.len() * 2);
                 stmts.push(lbl("start"));
+
+                ctx.push_vars();
+
+                for (name, _) in args {
+                    ctx.bind_var(name);
+                }
+
                 lower_stmts(m_stmts, &mut stmts, ctx);
+                ctx.pop_vars();
 
-                Some(Decl::FuncDecl(
+                // The start label stays first so the allocs are in the start block
+                stmts.splice(1..1, ctx.take_allocs());
+
+                // QBE requires the last block to end in a jump, void funcs that fall off the end
+                // return and anything else left open is unreachable
+                if !ends_block(&stmts) {
+                    stmts.push(match (r#type, noreturn) {
+                        (None, false) => Stmt::Ret(None),
+                        _ => Stmt::Hlt,
+                    });
+                }
+
+                lowered.push(Decl::FuncDecl(
                     name.to_string(),
-                    lower_visibility(visibility),
+                    lower_linkage(visibility, &[]),
                     lower_opt_type(r#type),
                     lower_args(args),
                     *variadic,
                     stmts,
-                ))
+                ));
             }
-            m::Decl::FwdDecl(_, _, _, _, _) => None,
-        })
-        .collect()
+            m::Decl::FwdDecl(name, _, _, _, _, noreturn) => {
+                if *noreturn {
+                    ctx.add_noreturn(name);
+                }
+            }
+            // Imported funcs are linked from their own module, only their noreturn flags matter here
+            m::Decl::Import(name) => {
+                for module in modules.iter().filter(|module| module.name == *name) {
+                    for decl in &module.decls {
+                        if let m::Decl::FuncDecl(name, m::Visibility::Public, _, _, _, true, _) =
+                            decl
+                        {
+                            ctx.add_noreturn(name);
+                        }
+                    }
+                }
+            }
+            m::Decl::Loc(_) => (),
+            m::Decl::StructDecl(name, fields) => {
+                let struct_layout = lower_struct_layout(fields, ctx);
+                let types = struct_layout.2.iter().map(|f| f.2.clone()).collect();
+
+                ctx.add_struct_layout(name, struct_layout);
+
+                lowered.push(Decl::Type(name.to_string(), types));
+            }
+        }
+    }
+
+    lowered
+}
+
+fn first_loc_file(stmts: &[m::Stmt]) -> Option<&str> {
+    m::located(stmts).find_map(|(loc, stmt)| match (loc, stmt) {
+        (Some(m::Loc::Pos(file, _, _)), _) => Some(file as &str),
+        (_, m::Stmt::Cond(cases)) => cases.iter().find_map(|(_, stmts)| first_loc_file(stmts)),
+        (_, m::Stmt::While(_, stmts)) => first_loc_file(stmts),
+        _ => None,
+    })
+}
+
+fn lower_data_fields(expr: &m::Expr, fields: &mut Vec<DataField>, ctx: &mut LoweringCtx) {
+    match expr {
+        m::Expr::ArrayLit(_, exprs) => {
+            for expr in exprs {
+                lower_data_fields(expr, fields, ctx);
+            }
+        }
+        m::Expr::ConstBool(b) => fields.push(DataField::Value(Type::W, (*b as i32).to_string())),
+        m::Expr::ConstDouble(d) if !d.is_finite() => {
+            fields.push(DataField::Value(Type::L, (d.to_bits() as i64).to_string()));
+        }
+        m::Expr::ConstDouble(d) => fields.push(DataField::Value(Type::D, double_lit(*d))),
+        m::Expr::ConstInt32(i) => fields.push(DataField::Value(Type::W, i.to_string())),
+        m::Expr::ConstFloat32(f) if !f.is_finite() => {
+            fields.push(DataField::Value(Type::W, (f.to_bits() as i32).to_string()));
+        }
+        m::Expr::ConstFloat32(f) => fields.push(DataField::Value(Type::S, single_lit(*f))),
+        m::Expr::ConstInt8(i) => fields.push(DataField::Value(Type::B, i.to_string())),
+        m::Expr::ConstInt16(i) => fields.push(DataField::Value(Type::H, i.to_string())),
+        m::Expr::ConstInt64(i) => fields.push(DataField::Value(Type::L, i.to_string())),
+        m::Expr::ConstUInt8(i) => fields.push(DataField::Value(Type::B, i.to_string())),
+        m::Expr::ConstUInt16(i) => fields.push(DataField::Value(Type::H, i.to_string())),
+        m::Expr::ConstUInt32(i) => fields.push(DataField::Value(Type::W, i.to_string())),
+        m::Expr::ConstUInt64(i) => fields.push(DataField::Value(Type::L, i.to_string())),
+        m::Expr::ConstStr(s) => {
+            let name = ctx.name_for_str(s);
+            fields.push(DataField::Value(Type::L, format!("${}", name)));
+        }
+        m::Expr::StructLit(r#type, field_inits) => {
+            let Type::Agg(struct_name) = lower_type(r#type) else {
+                panic!("Struct literal must be of a struct type");
+            };
+            let (size, _, field_layouts) = ctx.struct_layout(&struct_name).clone();
+            let mut offset = 0;
+
+            for (name, field_offset, r#type) in field_layouts {
+                let (_, expr) = field_inits
+                    .iter()
+                    .find(|f| f.0 == name)
+                    .expect("Struct literal does not initialize a declared field");
+
+                if field_offset > offset {
+                    fields.push(DataField::Zero(field_offset - offset));
+                }
+
+                lower_data_fields(expr, fields, ctx);
+                offset = field_offset + size_and_align(&r#type, ctx).0;
+            }
+
+            if size > offset {
+                fields.push(DataField::Zero(size - offset));
+            }
+        }
+        _ => panic!("Data must be initialized with a constant expression"),
+    }
+}
+
+fn is_aggregate(r#type: &m::Type) -> bool {
+    matches!(r#type, m::Type::Array(_, _) | m::Type::Struct(_))
+}
+
+fn lower_struct_layout(fields: &[m::StructField], ctx: &LoweringCtx) -> StructLayout {
+    let mut offset: usize = 0;
+    let mut struct_align = 1;
+    let mut field_layouts = Vec::with_capacity(fields.len());
+
+    for (name, r#type) in fields {
+        let r#type = lower_mem_type(r#type);
+        let (size, align) = size_and_align(&r#type, ctx);
+
+        offset = offset.next_multiple_of(align);
+        field_layouts.push((name.to_string(), offset, r#type));
+        offset += size;
+        struct_align = struct_align.max(align);
+    }
+
+    (
+        offset.next_multiple_of(struct_align),
+        struct_align,
+        field_layouts,
+    )
+}
+
+fn size_and_align(r#type: &Type, ctx: &LoweringCtx) -> (usize, usize) {
+    match r#type {
+        Type::Agg(name) => {
+            let (size, align, _) = ctx.struct_layout(name);
+            (*size, *align)
+        }
+        Type::B | Type::SB | Type::UB => (1, 1),
+        Type::H | Type::SH | Type::UH => (2, 2),
+        Type::S | Type::W => (4, 4),
+        Type::D | Type::L => (8, 8),
+    }
+}
+
+fn type_size_and_align(r#type: &m::Type, ctx: &LoweringCtx) -> (usize, usize) {
+    match r#type {
+        m::Type::Array(elem_type, len) => {
+            let (size, align) = type_size_and_align(elem_type, ctx);
+            (size * len, align)
+        }
+        // Elements keep their width in memory, the same as struct fields
+        _ => size_and_align(&lower_mem_type(r#type), ctx),
+    }
 }
 
 fn lower_args(args: &[m::FuncArg]) -> Vec<FuncArg> {
@@ -55,11 +258,42 @@ fn lower_args(args: &[m::FuncArg]) -> Vec<FuncArg> {
         .collect()
 }
 
+fn ends_block(stmts: &[Stmt]) -> bool {
+    matches!(
+        stmts.last(),
+        Some(Stmt::Hlt | Stmt::Jmp(_) | Stmt::Jnz(_, _, _) | Stmt::Ret(_))
+    )
+}
+
 fn lower_stmts(m_stmts: &[m::Stmt], stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) {
-    for stmt in m_stmts {
+    let mut located = m::located(m_stmts).peekable();
+
+    while let Some((loc, stmt)) = located.next() {
+        // Locs in a file other than the current dbgfile, or without a file at all, are dropped
+        if let Some(m::Loc::Pos(file, line, column)) = loc {
+            if ctx.dbg_file() == Some(file) {
+                stmts.push(Stmt::DbgLoc(*line, *column));
+            }
+        }
+
         ctx.push_tmp_refs();
 
         match stmt {
+            m::Stmt::Assign(name, expr) if ctx.global(name).is_some() => {
+                let value = lower_expr_to_value(expr, stmts, ctx);
+                let addr = Value::VarRef(name.to_string(), Type::L, ctx.global_scope(name));
+
+                stmts.push(store_elem(expr.r#type(), value, addr, ctx));
+            }
+            m::Stmt::Assign(name, expr) => {
+                let expr = lower_expr(expr, stmts, ctx);
+                stmts.push(Stmt::VarDecl(ctx.var(name), Scope::Func, expr));
+            }
+            m::Stmt::Break => {
+                let (_, end_lbl) = ctx.loop_lbls();
+                stmts.push(Stmt::Jmp(end_lbl.to_string()));
+                stmts.push(lbl(&ctx.uniq_name("after_break")));
+            }
             m::Stmt::Cond(cases) => {
                 let lbl_prefix = ctx.uniq_name("cond");
                 let end_lbl = format!("{}_end", lbl_prefix);
@@ -72,30 +306,97 @@ fn lower_stmts(m_stmts: &[m::Stmt], stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx
                     stmts.push(Stmt::Jnz(value, true_lbl.clone(), false_lbl.clone()));
                     stmts.push(lbl(&true_lbl));
 
+                    ctx.push_vars();
                     lower_stmts(case_stmts, stmts, ctx);
+                    ctx.pop_vars();
+
+                    if !ends_block(stmts) {
+                        stmts.push(Stmt::Jmp(end_lbl.clone()));
+                    }
 
-                    stmts.push(Stmt::Jmp(end_lbl.clone()));
                     stmts.push(lbl(&false_lbl));
                 }
 
                 stmts.push(Stmt::Lbl(end_lbl));
             }
+            m::Stmt::Continue => {
+                let (cond_lbl, _) = ctx.loop_lbls();
+                stmts.push(Stmt::Jmp(cond_lbl.to_string()));
+                stmts.push(lbl(&ctx.uniq_name("after_continue")));
+            }
             m::Stmt::FuncCall(name, exprs) => {
                 let values = lower_exprs_to_values(exprs, stmts, ctx);
                 stmts.push(Stmt::FuncCall(name.to_string(), values));
+
+                if ctx.noreturn(name) {
+                    stmts.push(Stmt::Hlt);
+                    stmts.push(lbl(&ctx.uniq_name("after_hlt")));
+                }
             }
+            m::Stmt::IndexAssign(name, index, expr) => {
+                // Array and pointer vars both hold an address so they are indexed the same way
+                let r#type = expr.r#type();
+                let (elem_size, _) = type_size_and_align(r#type, ctx);
+                let var = lower_var_ref(name, Type::L, Type::L, stmts, ctx);
+                let addr = elem_addr(&var, index, elem_size, stmts, ctx);
+                let value = lower_expr_to_value(expr, stmts, ctx);
+
+                stmts.push(store_elem(r#type, value, addr, ctx));
+            }
+            m::Stmt::Loc(_) => (),
             m::Stmt::Ret(Some(expr)) => {
                 let value = lower_expr_to_value(expr, stmts, ctx);
                 stmts.push(Stmt::Ret(Some(value)));
             }
             m::Stmt::Ret(None) => stmts.push(Stmt::Ret(None)),
-            m::Stmt::VarDecl(name, expr) => {
+            m::Stmt::Store(ptr, expr) => {
+                let addr = lower_expr_to_value(ptr, stmts, ctx);
+                let value = lower_expr_to_value(expr, stmts, ctx);
+
+                stmts.push(store_elem(expr.r#type(), value, addr, ctx));
+            }
+            m::Stmt::Unreachable => {
+                stmts.push(Stmt::Hlt);
+                stmts.push(lbl(&ctx.uniq_name("after_hlt")));
+            }
+            // The expr is lowered first so it still refers to any binding the var shadows
+            m::Stmt::VarDecl(name, expr) | m::Stmt::VarDeclMut(name, expr) => {
                 let expr = lower_expr(expr, stmts, ctx);
-                stmts.push(Stmt::VarDecl(name.to_string(), Scope::Func, expr));
+                stmts.push(Stmt::VarDecl(ctx.bind_var(name), Scope::Func, expr));
+            }
+            m::Stmt::While(expr, while_stmts) => {
+                let lbl_prefix = ctx.uniq_name("while");
+                let cond_lbl = format!("{}_cond", lbl_prefix);
+                let body_lbl = format!("{}_body", lbl_prefix);
+                let end_lbl = format!("{}_end", lbl_prefix);
+
+                stmts.push(lbl(&cond_lbl));
+
+                let value = lower_expr_to_value(expr, stmts, ctx);
+
+                stmts.push(Stmt::Jnz(value, body_lbl.clone(), end_lbl.clone()));
+                stmts.push(lbl(&body_lbl));
+
+                ctx.push_loop_lbls((cond_lbl.clone(), end_lbl.clone()));
+                ctx.push_vars();
+                lower_stmts(while_stmts, stmts, ctx);
+                ctx.pop_vars();
+                ctx.pop_loop_lbls();
+
+                if !ends_block(stmts) {
+                    stmts.push(Stmt::Jmp(cond_lbl));
+                }
+
+                stmts.push(Stmt::Lbl(end_lbl));
             }
         }
 
         deref_tmp_refs(stmts, ctx);
+
+        // Anything following a ret is unreachable but still needs its own block
+        if matches!(stmt, m::Stmt::Ret(_)) && located.peek().is_some() {
+            stmts.push(lbl(&ctx.uniq_name("after_ret")));
+        }
     }
 }
 
@@ -104,7 +405,7 @@ fn deref_tmp_refs(stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) {
 
     for (tmp_ref_name, var_name, var_type) in tmp_refs {
         let expr = Expr::Load(
-            var_type,
+            var_type.value_type(),
             var_type,
             Value::VarRef(tmp_ref_name, Type::L, Scope::Func),
         );
@@ -125,13 +426,55 @@ fn lower_exprs_to_values(
 
 fn lower_expr_to_value(expr: &m::Expr, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> Value {
     match expr {
+        m::Expr::And(lhs, rhs) => lower_short_circuit("and", true, lhs, rhs, stmts, ctx),
+        m::Expr::Arith(op, lhs, rhs) => {
+            let var_name = ctx.uniq_name(&op.to_string());
+            let expr = lower_arith(op, lhs, rhs, stmts, ctx);
+            let r#type = expr.r#type();
+
+            stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+            Value::VarRef(var_name.to_string(), r#type, Scope::Func)
+        }
+        m::Expr::Bitwise(op, lhs, rhs) => {
+            let var_name = ctx.uniq_name(&op.to_string());
+            let expr = lower_bitwise(op, lhs, rhs, stmts, ctx);
+            let r#type = expr.r#type();
+
+            stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+            Value::VarRef(var_name.to_string(), r#type, Scope::Func)
+        }
+        m::Expr::ArrayLit(r#type, exprs) => {
+            let m::Type::Array(elem_type, _) = r#type else {
+                panic!("Array literal must be of an array type");
+            };
+            let (size, _) = type_size_and_align(r#type, ctx);
+            let (elem_size, _) = type_size_and_align(elem_type, ctx);
+            let var_name = ctx.alloc("array", size);
+            let value = Value::VarRef(var_name, Type::L, Scope::Func);
+
+            for (i, expr) in exprs.iter().enumerate() {
+                let elem_value = lower_expr_to_value(expr, stmts, ctx);
+                let addr = field_addr(&value, i * elem_size, stmts, ctx);
+
+                stmts.push(store_elem(elem_type, elem_value, addr, ctx));
+            }
+
+            value
+        }
+        m::Expr::Cast(r#type, expr) => {
+            let var_name = ctx.uniq_name("cast");
+            let expr = lower_cast(r#type, expr, stmts, ctx);
+            let r#type = expr.r#type();
+
+            stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+            Value::VarRef(var_name, r#type, Scope::Func)
+        }
         m::Expr::Cmp(op, lhs, rhs) => {
             let var_name = ctx.uniq_name("cmp");
-            let expr = Expr::Cmp(
-                lower_op(op),
-                lower_expr_to_value(lhs, stmts, ctx),
-                lower_expr_to_value(rhs, stmts, ctx),
-            );
+            let expr = lower_cmp(op, lhs, rhs, stmts, ctx);
 
             stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
 
@@ -139,13 +482,54 @@ fn lower_expr_to_value(expr: &m::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Lowering
         }
         m::Expr::ConstBool(true) => Value::ConstW(1),
         m::Expr::ConstBool(false) => Value::ConstW(0),
+        m::Expr::ConstDouble(d) if !d.is_finite() => {
+            float_bits(Type::D, Value::ConstL(d.to_bits() as i64), stmts, ctx)
+        }
         m::Expr::ConstDouble(d) => Value::ConstD(*d),
+        m::Expr::ConstFloat32(f) if !f.is_finite() => {
+            float_bits(Type::S, Value::ConstW(f.to_bits() as i32), stmts, ctx)
+        }
+        m::Expr::ConstFloat32(f) => Value::ConstS(*f),
+        m::Expr::ConstInt8(i) => Value::ConstW(*i as i32),
+        m::Expr::ConstInt16(i) => Value::ConstW(*i as i32),
         m::Expr::ConstInt32(i) => Value::ConstW(*i),
         m::Expr::ConstInt64(i) => Value::ConstL(*i),
+        // Unsigned constants keep their bit pattern, the operations decide how it is read
+        m::Expr::ConstUInt8(i) => Value::ConstW(*i as i32),
+        m::Expr::ConstUInt16(i) => Value::ConstW(*i as i32),
+        m::Expr::ConstUInt32(i) => Value::ConstW(*i as i32),
+        m::Expr::ConstUInt64(i) => Value::ConstL(*i as i64),
         m::Expr::ConstStr(s) => {
             let name = ctx.name_for_str(s);
             Value::VarRef(name, Type::L, Scope::Global)
         }
+        m::Expr::Field(expr, name, _) => {
+            let value = lower_expr_to_value(expr, stmts, ctx);
+            let Type::Agg(struct_name) = value.r#type() else {
+                panic!("Field access '{}' requires a value of a struct type", name);
+            };
+            let (_, offset, r#type) = ctx
+                .struct_layout(&struct_name)
+                .2
+                .iter()
+                .find(|f| f.0 == *name)
+                .expect("Field access of an undeclared field")
+                .clone();
+            let addr = field_addr(&value, offset, stmts, ctx);
+
+            match r#type {
+                Type::Agg(_) => with_type(addr, r#type),
+                _ => {
+                    let var_name = ctx.uniq_name("field");
+                    let value_type = r#type.value_type();
+                    let expr = Expr::Load(value_type.clone(), r#type, addr);
+
+                    stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+                    Value::VarRef(var_name, value_type, Scope::Func)
+                }
+            }
+        }
         m::Expr::FuncCall(name, r#type, exprs) => {
             let expr = lower_func_call(name, r#type, exprs, stmts, ctx);
             let r#type = expr.r#type();
@@ -155,6 +539,26 @@ fn lower_expr_to_value(expr: &m::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Lowering
 
             Value::VarRef(name, r#type, Scope::Func)
         }
+        m::Expr::Index(expr, index, r#type) => {
+            let value = lower_expr_to_value(expr, stmts, ctx);
+            let (elem_size, _) = type_size_and_align(r#type, ctx);
+            let addr = elem_addr(&value, index, elem_size, stmts, ctx);
+
+            load(r#type, addr, stmts, ctx)
+        }
+        m::Expr::Load(expr, r#type) => {
+            let addr = lower_expr_to_value(expr, stmts, ctx);
+            load(r#type, addr, stmts, ctx)
+        }
+        m::Expr::Neg(expr) => {
+            let var_name = ctx.uniq_name("neg");
+            let expr = lower_neg(expr, stmts, ctx);
+            let r#type = expr.r#type();
+
+            stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+            Value::VarRef(var_name.to_string(), r#type, Scope::Func)
+        }
         m::Expr::Not(expr) => {
             let var_name = ctx.uniq_name("not");
             let value = lower_expr_to_value(expr, stmts, ctx);
@@ -164,48 +568,141 @@ fn lower_expr_to_value(expr: &m::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Lowering
 
             Value::VarRef(var_name.to_string(), Type::W, Scope::Func)
         }
-        m::Expr::VarRef(name, r#type, true) => {
-            let tmp_ref_name = ctx.uniq_name("ref");
+        m::Expr::Or(lhs, rhs) => lower_short_circuit("or", false, lhs, rhs, stmts, ctx),
+        m::Expr::PtrAdd(expr, index) => {
+            let m::Type::Ptr(Some(elem_type)) = expr.r#type() else {
+                panic!("Pointer arithmetic requires an expression of a typed pointer type");
+            };
+            let value = lower_expr_to_value(expr, stmts, ctx);
+            let (elem_size, _) = type_size_and_align(elem_type, ctx);
+
+            elem_addr(&value, index, elem_size, stmts, ctx)
+        }
+        m::Expr::StructLit(r#type, field_inits) => {
             let r#type = lower_type(r#type);
-            let tmp_ref = (tmp_ref_name.to_string(), name.to_string(), r#type);
+            let Type::Agg(struct_name) = &r#type else {
+                panic!("Struct literal must be of a struct type");
+            };
+            let (size, _, field_layouts) = ctx.struct_layout(struct_name).clone();
+            let var_name = ctx.alloc("struct", size);
+            let value = Value::VarRef(var_name, r#type, Scope::Func);
+
+            for (name, expr) in field_inits {
+                let (_, offset, r#type) = field_layouts
+                    .iter()
+                    .find(|f| f.0 == *name)
+                    .expect("Struct literal initializes an undeclared field");
+                let field_value = lower_expr_to_value(expr, stmts, ctx);
+                let addr = field_addr(&value, *offset, stmts, ctx);
+
+                stmts.push(store(r#type, field_value, addr, ctx));
+            }
+
+            value
+        }
+        m::Expr::VarRef(name, _, true) if ctx.global(name).is_some() => {
+            Value::VarRef(name.to_string(), Type::L, ctx.global_scope(name))
+        }
+        m::Expr::VarRef(name, m::Type::Array(_, _) | m::Type::Struct(_), true) => {
+            Value::VarRef(ctx.var(name), Type::L, Scope::Func)
+        }
+        m::Expr::VarRef(name, r#type, true) => {
+            let tmp_ref_name = ctx.alloc("ref", 8);
+            let name = ctx.var(name);
+            let r#type = lower_mem_type(r#type);
+            let tmp_ref = (tmp_ref_name.to_string(), name.to_string(), r#type.clone());
 
             ctx.add_tmp_ref(tmp_ref);
 
-            stmts.push(Stmt::VarDecl(
-                tmp_ref_name.to_string(),
-                Scope::Func,
-                Expr::Alloc8(8),
-            ));
             stmts.push(Stmt::Store(
-                r#type,
-                Value::VarRef(name.to_string(), r#type, Scope::Func),
+                r#type.clone(),
+                Value::VarRef(name.to_string(), r#type.value_type(), Scope::Func),
                 Value::VarRef(tmp_ref_name.to_string(), Type::L, Scope::Func),
             ));
 
             Value::VarRef(tmp_ref_name.to_string(), Type::L, Scope::Func)
         }
         m::Expr::VarRef(name, r#type, false) => {
-            Value::VarRef(name.to_string(), lower_type(r#type), Scope::Func)
+            lower_var_ref(name, lower_type(r#type), lower_mem_type(r#type), stmts, ctx)
+        }
+    }
+}
+
+fn float_bits(r#type: Type, bits: Value, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> Value {
+    let var_name = ctx.uniq_name("float");
+
+    stmts.push(Stmt::VarDecl(
+        var_name.to_string(),
+        Scope::Func,
+        Expr::Conv(Conv::Cast, r#type.clone(), bits),
+    ));
+
+    Value::VarRef(var_name, r#type, Scope::Func)
+}
+
+fn lower_var_ref(
+    name: &str,
+    r#type: Type,
+    mem_type: Type,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut LoweringCtx,
+) -> Value {
+    match ctx.global(name) {
+        // Aggregates are referenced by address, scalars are loaded from their data
+        Some(true) => Value::VarRef(name.to_string(), r#type, ctx.global_scope(name)),
+        Some(false) => {
+            let var_name = ctx.uniq_name("global");
+            let addr = Value::VarRef(name.to_string(), Type::L, ctx.global_scope(name));
+            let expr = Expr::Load(r#type.clone(), mem_type, addr);
+
+            stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+            Value::VarRef(var_name, r#type, Scope::Func)
         }
+        None => Value::VarRef(ctx.var(name), r#type, Scope::Func),
     }
 }
 
 fn lower_expr(expr: &m::Expr, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> Expr {
     match expr {
-        m::Expr::Cmp(op, lhs, rhs) => Expr::Cmp(
-            lower_op(op),
-            lower_expr_to_value(lhs, stmts, ctx),
-            lower_expr_to_value(rhs, stmts, ctx),
-        ),
-        m::Expr::ConstBool(_)
+        m::Expr::Arith(op, lhs, rhs) => lower_arith(op, lhs, rhs, stmts, ctx),
+        m::Expr::Bitwise(op, lhs, rhs) => lower_bitwise(op, lhs, rhs, stmts, ctx),
+        m::Expr::Field(_, _, m::Type::Struct(_))
+        | m::Expr::Index(_, _, m::Type::Array(_, _) | m::Type::Struct(_))
+        | m::Expr::Load(_, m::Type::Array(_, _) | m::Type::Struct(_))
+        | m::Expr::VarRef(_, m::Type::Array(_, _) | m::Type::Struct(_), false) => {
+            // Binding an existing struct or array copies it so a byref use of one binding does
+            // not change the other
+            let value = lower_expr_to_value(expr, stmts, ctx);
+            Expr::Value(copy_agg(value, expr.r#type(), stmts, ctx))
+        }
+        m::Expr::Cast(r#type, expr) => lower_cast(r#type, expr, stmts, ctx),
+        m::Expr::Cmp(op, lhs, rhs) => lower_cmp(op, lhs, rhs, stmts, ctx),
+        m::Expr::And(_, _)
+        | m::Expr::ArrayLit(_, _)
+        | m::Expr::ConstBool(_)
         | m::Expr::ConstDouble(_)
+        | m::Expr::ConstFloat32(_)
+        | m::Expr::ConstInt8(_)
+        | m::Expr::ConstInt16(_)
         | m::Expr::ConstInt32(_)
         | m::Expr::ConstInt64(_)
         | m::Expr::ConstStr(_)
+        | m::Expr::ConstUInt8(_)
+        | m::Expr::ConstUInt16(_)
+        | m::Expr::ConstUInt32(_)
+        | m::Expr::ConstUInt64(_)
+        | m::Expr::Field(_, _, _)
+        | m::Expr::Index(_, _, _)
+        | m::Expr::Load(_, _)
+        | m::Expr::Or(_, _)
+        | m::Expr::PtrAdd(_, _)
+        | m::Expr::StructLit(_, _)
         | m::Expr::VarRef(_, _, _) => {
             let value = lower_expr_to_value(expr, stmts, ctx);
             Expr::Value(value)
         }
+        m::Expr::Neg(expr) => lower_neg(expr, stmts, ctx),
         m::Expr::Not(_) => {
             let value = lower_expr_to_value(expr, stmts, ctx);
             Expr::Sub(Value::ConstW(1), value)
@@ -214,6 +711,342 @@ fn lower_expr(expr: &m::Expr, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> E
     }
 }
 
+fn lower_arith(
+    op: &m::ArithOp,
+    lhs: &m::Expr,
+    rhs: &m::Expr,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut LoweringCtx,
+) -> Expr {
+    let r#type = lhs.r#type();
+    let unsigned = r#type.is_unsigned_int();
+    let lhs = lower_expr_to_value(lhs, stmts, ctx);
+    let rhs = lower_expr_to_value(rhs, stmts, ctx);
+
+    let expr = match op {
+        m::ArithOp::Add => Expr::Add(lhs, rhs),
+        m::ArithOp::Sub => Expr::Sub(lhs, rhs),
+        m::ArithOp::Mul => Expr::Mul(lhs, rhs),
+        m::ArithOp::Div if unsigned => Expr::UDiv(lhs, rhs),
+        m::ArithOp::Div => Expr::Div(lhs, rhs),
+        m::ArithOp::Rem if unsigned => Expr::URem(lhs, rhs),
+        m::ArithOp::Rem => Expr::Rem(lhs, rhs),
+    };
+
+    wrap_sub_word(expr, r#type, stmts, ctx)
+}
+
+fn lower_bitwise(
+    op: &m::BitOp,
+    lhs: &m::Expr,
+    rhs: &m::Expr,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut LoweringCtx,
+) -> Expr {
+    let r#type = lhs.r#type();
+    let lhs = lower_expr_to_value(lhs, stmts, ctx);
+    let rhs = lower_expr_to_value(rhs, stmts, ctx);
+
+    let expr = match op {
+        m::BitOp::And => Expr::And(lhs, rhs),
+        m::BitOp::Or => Expr::Or(lhs, rhs),
+        m::BitOp::Xor => Expr::Xor(lhs, rhs),
+        m::BitOp::Shl => Expr::Shl(lhs, rhs),
+        m::BitOp::Sar => Expr::Sar(lhs, rhs),
+        m::BitOp::Shr => {
+            // A logical shift of a signed sub-word value must not shift in its sign extension
+            let lhs = match sub_word_ext(r#type) {
+                Some(Conv::Extsb) => zero_ext(Conv::Extub, lhs, stmts, ctx),
+                Some(Conv::Extsh) => zero_ext(Conv::Extuh, lhs, stmts, ctx),
+                _ => lhs,
+            };
+            Expr::Shr(lhs, rhs)
+        }
+    };
+
+    wrap_sub_word(expr, r#type, stmts, ctx)
+}
+
+fn lower_neg(expr: &m::Expr, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> Expr {
+    let value = lower_expr_to_value(expr, stmts, ctx);
+    wrap_sub_word(Expr::Neg(value), expr.r#type(), stmts, ctx)
+}
+
+// Sub-word values are kept in words sign or zero extended from their width, results that can
+// leave that range are wrapped back into it
+fn wrap_sub_word(
+    expr: Expr,
+    r#type: &m::Type,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut LoweringCtx,
+) -> Expr {
+    let Some(conv) = sub_word_ext(r#type) else {
+        return expr;
+    };
+    let var_name = ctx.uniq_name("wide");
+
+    stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+    Expr::Conv(conv, Type::W, Value::VarRef(var_name, Type::W, Scope::Func))
+}
+
+fn sub_word_ext(r#type: &m::Type) -> Option<Conv> {
+    match r#type {
+        m::Type::Int8 => Some(Conv::Extsb),
+        m::Type::Int16 => Some(Conv::Extsh),
+        m::Type::UInt8 => Some(Conv::Extub),
+        m::Type::UInt16 => Some(Conv::Extuh),
+        _ => None,
+    }
+}
+
+fn zero_ext(conv: Conv, value: Value, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> Value {
+    let var_name = ctx.uniq_name(&conv.to_string());
+
+    stmts.push(Stmt::VarDecl(
+        var_name.to_string(),
+        Scope::Func,
+        Expr::Conv(conv, Type::W, value),
+    ));
+
+    Value::VarRef(var_name, Type::W, Scope::Func)
+}
+
+fn lower_cmp(
+    op: &m::Op,
+    lhs: &m::Expr,
+    rhs: &m::Expr,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut LoweringCtx,
+) -> Expr {
+    let op = lower_op(op, lhs.r#type());
+    let lhs = lower_expr_to_value(lhs, stmts, ctx);
+    let rhs = lower_expr_to_value(rhs, stmts, ctx);
+
+    Expr::Cmp(op, lhs, rhs)
+}
+
+fn lower_short_circuit(
+    name: &str,
+    eval_rhs_if: bool,
+    lhs: &m::Expr,
+    rhs: &m::Expr,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut LoweringCtx,
+) -> Value {
+    // The result temp is assigned on both paths, QBE's SSA construction joins them with a phi
+    let var_name = ctx.uniq_name(name);
+    let rhs_lbl = format!("{}_rhs", var_name);
+    let end_lbl = format!("{}_end", var_name);
+    let var = Value::VarRef(var_name.to_string(), Type::W, Scope::Func);
+
+    let lhs = lower_expr_to_value(lhs, stmts, ctx);
+    stmts.push(Stmt::VarDecl(
+        var_name.to_string(),
+        Scope::Func,
+        Expr::Value(lhs),
+    ));
+
+    stmts.push(if eval_rhs_if {
+        Stmt::Jnz(var.clone(), rhs_lbl.clone(), end_lbl.clone())
+    } else {
+        Stmt::Jnz(var.clone(), end_lbl.clone(), rhs_lbl.clone())
+    });
+    stmts.push(Stmt::Lbl(rhs_lbl));
+
+    let rhs = lower_expr_to_value(rhs, stmts, ctx);
+    stmts.push(Stmt::VarDecl(var_name, Scope::Func, Expr::Value(rhs)));
+    stmts.push(Stmt::Lbl(end_lbl));
+
+    var
+}
+
+fn lower_cast(
+    r#type: &m::Type,
+    expr: &m::Expr,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut LoweringCtx,
+) -> Expr {
+    let from = expr.r#type();
+    let from_type = lower_type(from);
+    let to_type = lower_type(r#type);
+    let value = lower_expr_to_value(expr, stmts, ctx);
+
+    match (from, r#type) {
+        (from, to) if from == to => Expr::Conv(Conv::Copy, to_type, value),
+        (_, m::Type::Bool) => match from_type {
+            Type::L => Expr::Cmp(Op::Ne, value, Value::ConstL(0)),
+            _ => Expr::Cmp(Op::Ne, value, Value::ConstW(0)),
+        },
+        (m::Type::Float32, m::Type::Double) => Expr::Conv(Conv::Exts, to_type, value),
+        (m::Type::Double, m::Type::Float32) => Expr::Conv(Conv::Truncd, to_type, value),
+        (from, to) if to.is_float() => {
+            let conv = match (from_type, from.is_unsigned_int()) {
+                (Type::L, false) => Conv::Sltof,
+                (Type::L, true) => Conv::Ultof,
+                (_, false) => Conv::Swtof,
+                (_, true) => Conv::Uwtof,
+            };
+            Expr::Conv(conv, to_type, value)
+        }
+        (from, to) if from.is_float() => {
+            let conv = match (from, to.is_unsigned_int()) {
+                (m::Type::Float32, false) => Conv::Stosi,
+                (m::Type::Float32, true) => Conv::Stoui,
+                (_, false) => Conv::Dtosi,
+                (_, true) => Conv::Dtoui,
+            };
+            wrap_sub_word(Expr::Conv(conv, to_type, value), to, stmts, ctx)
+        }
+        (from, to) => match (from_type, &to_type, sub_word_ext(to)) {
+            (Type::W, Type::L, _) if from.is_signed_int() => {
+                Expr::Conv(Conv::Extsw, to_type, value)
+            }
+            (Type::W, Type::L, _) => Expr::Conv(Conv::Extuw, to_type, value),
+            (_, _, Some(conv)) => Expr::Conv(conv, to_type, value),
+            // Same width conversions and truncating a long to a word only need a copy since
+            // QBE uses the lower 32 bits of a long where a word is expected
+            _ => Expr::Conv(Conv::Copy, to_type, value),
+        },
+    }
+}
+
+fn field_addr(value: &Value, offset: usize, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> Value {
+    let ptr = with_type(value.clone(), Type::L);
+
+    if offset == 0 {
+        return ptr;
+    }
+
+    let var_name = ctx.uniq_name("offset");
+    let expr = Expr::Add(ptr, Value::ConstL(offset as i64));
+
+    stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+    Value::VarRef(var_name, Type::L, Scope::Func)
+}
+
+fn elem_addr(
+    value: &Value,
+    index: &m::Expr,
+    elem_size: usize,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut LoweringCtx,
+) -> Value {
+    let const_index = match index {
+        m::Expr::ConstInt8(i) => Some(*i as i64),
+        m::Expr::ConstInt16(i) => Some(*i as i64),
+        m::Expr::ConstInt32(i) => Some(*i as i64),
+        m::Expr::ConstInt64(i) => Some(*i),
+        m::Expr::ConstUInt8(i) => Some(*i as i64),
+        m::Expr::ConstUInt16(i) => Some(*i as i64),
+        m::Expr::ConstUInt32(i) => Some(*i as i64),
+        _ => None,
+    };
+
+    if let Some(i) = const_index.filter(|i| *i >= 0) {
+        return field_addr(value, i as usize * elem_size, stmts, ctx);
+    }
+
+    let conv = match index.r#type().is_unsigned_int() {
+        true => Conv::Extuw,
+        false => Conv::Extsw,
+    };
+    let index = match lower_expr_to_value(index, stmts, ctx) {
+        index @ Value::VarRef(_, Type::W, _) => {
+            let var_name = ctx.uniq_name(&conv.to_string());
+            stmts.push(Stmt::VarDecl(
+                var_name.to_string(),
+                Scope::Func,
+                Expr::Conv(conv, Type::L, index),
+            ));
+            Value::VarRef(var_name, Type::L, Scope::Func)
+        }
+        Value::ConstW(i) => Value::ConstL(i as i64),
+        index => index,
+    };
+    let offset_name = ctx.uniq_name("offset");
+    let addr_name = ctx.uniq_name("elem");
+
+    stmts.push(Stmt::VarDecl(
+        offset_name.to_string(),
+        Scope::Func,
+        Expr::Mul(index, Value::ConstL(elem_size as i64)),
+    ));
+    stmts.push(Stmt::VarDecl(
+        addr_name.to_string(),
+        Scope::Func,
+        Expr::Add(
+            with_type(value.clone(), Type::L),
+            Value::VarRef(offset_name, Type::L, Scope::Func),
+        ),
+    ));
+
+    Value::VarRef(addr_name, Type::L, Scope::Func)
+}
+
+fn load(r#type: &m::Type, addr: Value, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> Value {
+    match r#type {
+        m::Type::Array(_, _) => with_type(addr, Type::L),
+        m::Type::Struct(_) => with_type(addr, lower_type(r#type)),
+        _ => {
+            let mem_type = lower_mem_type(r#type);
+            let r#type = lower_type(r#type);
+            let var_name = ctx.uniq_name("load");
+            let expr = Expr::Load(r#type.clone(), mem_type, with_type(addr, Type::L));
+
+            stmts.push(Stmt::VarDecl(var_name.to_string(), Scope::Func, expr));
+
+            Value::VarRef(var_name, r#type, Scope::Func)
+        }
+    }
+}
+
+fn copy_agg(value: Value, r#type: &m::Type, stmts: &mut Vec<Stmt>, ctx: &mut LoweringCtx) -> Value {
+    let (size, _) = type_size_and_align(r#type, ctx);
+    let var_name = match r#type {
+        m::Type::Array(_, _) => ctx.alloc("array", size),
+        _ => ctx.alloc("struct", size),
+    };
+    let copy = Value::VarRef(var_name, value.r#type(), Scope::Func);
+
+    stmts.push(store_elem(r#type, value, copy.clone(), ctx));
+
+    copy
+}
+
+fn store_elem(r#type: &m::Type, value: Value, addr: Value, ctx: &LoweringCtx) -> Stmt {
+    match r#type {
+        m::Type::Array(_, _) => {
+            let (size, _) = type_size_and_align(r#type, ctx);
+            Stmt::Blit(value, with_type(addr, Type::L), size)
+        }
+        _ => store(
+            &lower_mem_type(r#type),
+            value,
+            with_type(addr, Type::L),
+            ctx,
+        ),
+    }
+}
+
+fn store(r#type: &Type, value: Value, addr: Value, ctx: &LoweringCtx) -> Stmt {
+    match r#type {
+        Type::Agg(_) => {
+            let (size, _) = size_and_align(r#type, ctx);
+            Stmt::Blit(with_type(value, Type::L), with_type(addr, Type::L), size)
+        }
+        _ => Stmt::Store(r#type.clone(), value, addr),
+    }
+}
+
+fn with_type(value: Value, r#type: Type) -> Value {
+    match value {
+        Value::VarRef(name, _, scope) => Value::VarRef(name, r#type, scope),
+        _ => value,
+    }
+}
+
 fn lower_func_call(
     name: &str,
     r#type: &m::Type,
@@ -225,18 +1058,56 @@ fn lower_func_call(
     Expr::FuncCall(name.to_string(), lower_type(r#type), values)
 }
 
-fn lower_visibility(visibility: &m::Visibility) -> Option<Linkage> {
-    match visibility {
+fn lower_linkage(visibility: &m::Visibility, linkage: &[m::Linkage]) -> Vec<Linkage> {
+    let export = match visibility {
         m::Visibility::Public => Some(Linkage::Export),
         m::Visibility::Private => None,
+    };
+
+    export
+        .into_iter()
+        .chain(linkage.iter().map(|l| match l {
+            m::Linkage::Section(name, flags) => Linkage::Section(name.to_string(), flags.clone()),
+            m::Linkage::Thread => Linkage::Thread,
+        }))
+        .collect()
+}
+
+fn data_scope(linkage: &[m::Linkage]) -> Scope {
+    match linkage.contains(&m::Linkage::Thread) {
+        true => Scope::Thread,
+        false => Scope::Global,
     }
 }
 
 fn lower_type(r#type: &m::Type) -> Type {
     match r#type {
         m::Type::Double => Type::D,
-        m::Type::Bool | m::Type::Int32 => Type::W,
-        m::Type::Int64 | m::Type::Ptr(_) | m::Type::Str => Type::L,
+        m::Type::Float32 => Type::S,
+        m::Type::Bool
+        | m::Type::Int8
+        | m::Type::Int16
+        | m::Type::Int32
+        | m::Type::UInt8
+        | m::Type::UInt16
+        | m::Type::UInt32 => Type::W,
+        m::Type::Array(_, _)
+        | m::Type::Int64
+        | m::Type::Ptr(_)
+        | m::Type::Str
+        | m::Type::UInt64 => Type::L,
+        m::Type::Struct(name) => Type::Agg(name.to_string()),
+    }
+}
+
+// Sub-word integers are operated on as words but keep their width and signedness in memory
+fn lower_mem_type(r#type: &m::Type) -> Type {
+    match r#type {
+        m::Type::Int8 => Type::SB,
+        m::Type::Int16 => Type::SH,
+        m::Type::UInt8 => Type::UB,
+        m::Type::UInt16 => Type::UH,
+        _ => lower_type(r#type),
     }
 }
 
@@ -244,10 +1115,26 @@ fn lower_opt_type(r#type: &Option<m::Type>) -> Option<Type> {
     r#type.as_ref().map(lower_type)
 }
 
-fn lower_op(op: &m::Op) -> Op {
-    match op {
-        m::Op::Eq => Op::Eq,
-        m::Op::Ne => Op::Ne,
+fn lower_op(op: &m::Op, r#type: &m::Type) -> Op {
+    match (op, r#type) {
+        (m::Op::Eq, _) => Op::Eq,
+        (m::Op::Ne, _) => Op::Ne,
+        (m::Op::Lt, t) if t.is_float() => Op::Lt,
+        (m::Op::Le, t) if t.is_float() => Op::Le,
+        (m::Op::Gt, t) if t.is_float() => Op::Gt,
+        (m::Op::Ge, t) if t.is_float() => Op::Ge,
+        (m::Op::Lt, t) if t.is_unsigned_int() => Op::Ult,
+        (m::Op::Le, t) if t.is_unsigned_int() => Op::Ule,
+        (m::Op::Gt, t) if t.is_unsigned_int() => Op::Ugt,
+        (m::Op::Ge, t) if t.is_unsigned_int() => Op::Uge,
+        (m::Op::Lt, _) => Op::Slt,
+        (m::Op::Le, _) => Op::Sle,
+        (m::Op::Gt, _) => Op::Sgt,
+        (m::Op::Ge, _) => Op::Sge,
+        (m::Op::ULt, _) => Op::Ult,
+        (m::Op::ULe, _) => Op::Ule,
+        (m::Op::UGt, _) => Op::Ugt,
+        (m::Op::UGe, _) => Op::Uge,
     }
 }
 
@@ -6,24 +6,36 @@ pub struct CompUnit {
     pub decls: Vec<Decl>,
 }
 
-pub type DataField = (Type, String);
+pub type Column = u32;
 pub type FuncArg = (String, Type);
+pub type Line = u32;
 pub type Variadic = bool;
 
+#[allow(clippy::enum_variant_names)]
 pub enum Decl {
-    Data(String, Vec<DataField>),
+    Data(String, Vec<Linkage>, Vec<DataField>),
+    DbgFile(String),
     FuncDecl(
         String,
-        Option<Linkage>,
+        Vec<Linkage>,
         Option<Type>,
         Vec<FuncArg>,
         Variadic,
         Vec<Stmt>,
     ),
+    Type(String, Vec<Type>),
+}
+
+pub enum DataField {
+    Value(Type, String),
+    Zero(usize),
 }
 
 pub enum Stmt {
+    Blit(Value, Value, usize),
+    DbgLoc(Line, Option<Column>),
     FuncCall(String, Vec<Value>),
+    Hlt,
     Jmp(String),
     Jnz(Value, String, String),
     Lbl(String),
@@ -33,41 +45,101 @@ pub enum Stmt {
 }
 
 pub enum Expr {
+    Add(Value, Value),
     Alloc8(usize),
+    And(Value, Value),
     Cmp(Op, Value, Value),
+    Conv(Conv, Type, Value),
+    Div(Value, Value),
     Load(Type, Type, Value),
+    Mul(Value, Value),
+    Neg(Value),
+    Or(Value, Value),
+    Rem(Value, Value),
+    Sar(Value, Value),
+    Shl(Value, Value),
+    Shr(Value, Value),
     Sub(Value, Value),
+    UDiv(Value, Value),
+    URem(Value, Value),
     Value(Value),
     FuncCall(String, Type, Vec<Value>),
+    Xor(Value, Value),
 }
 
+#[derive(Clone)]
 pub enum Value {
     ConstD(f64),
     ConstL(i64),
+    ConstS(f32),
     ConstW(i32),
     VarRef(String, Type, Scope),
 }
 
+pub enum Conv {
+    Cast,
+    Copy,
+    Dtosi,
+    Dtoui,
+    Exts,
+    Extsb,
+    Extsh,
+    Extsw,
+    Extub,
+    Extuh,
+    Extuw,
+    Sltof,
+    Stosi,
+    Stoui,
+    Swtof,
+    Truncd,
+    Ultof,
+    Uwtof,
+}
+
 pub enum Linkage {
     Export,
+    Section(String, Option<String>),
+    Thread,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum Type {
+    Agg(String),
     B,
     D,
+    H,
     L,
+    S,
+    SB,
+    SH,
+    UB,
+    UH,
     W,
 }
 
+#[derive(Clone)]
 pub enum Scope {
     Func,
     Global,
+    Thread,
 }
 
 pub enum Op {
     Eq,
     Ne,
+    Sle,
+    Slt,
+    Sge,
+    Sgt,
+    Ule,
+    Ult,
+    Uge,
+    Ugt,
+    Le,
+    Lt,
+    Ge,
+    Gt,
 }
 
 pub trait Typed {
@@ -77,12 +149,26 @@ pub trait Typed {
 impl Typed for Expr {
     fn r#type(&self) -> Type {
         match self {
+            Expr::Add(value, _) => value.r#type(),
             Expr::Alloc8(_) => Type::L,
+            Expr::And(value, _) => value.r#type(),
             Expr::Cmp(_, _, _) => Type::W,
-            Expr::Load(r#type, _, _) => *r#type,
+            Expr::Conv(_, r#type, _) => r#type.clone(),
+            Expr::Div(value, _) => value.r#type(),
+            Expr::Load(r#type, _, _) => r#type.clone(),
+            Expr::Mul(value, _) => value.r#type(),
+            Expr::Neg(value) => value.r#type(),
+            Expr::Or(value, _) => value.r#type(),
+            Expr::Rem(value, _) => value.r#type(),
+            Expr::Sar(value, _) => value.r#type(),
+            Expr::Shl(value, _) => value.r#type(),
+            Expr::Shr(value, _) => value.r#type(),
             Expr::Sub(value, _) => value.r#type(),
+            Expr::UDiv(value, _) => value.r#type(),
+            Expr::URem(value, _) => value.r#type(),
             Expr::Value(value) => value.r#type(),
-            Expr::FuncCall(_, r#type, _) => *r#type,
+            Expr::FuncCall(_, r#type, _) => r#type.clone(),
+            Expr::Xor(value, _) => value.r#type(),
         }
     }
 }
@@ -92,8 +178,74 @@ impl Typed for Value {
         match self {
             Value::ConstD(_) => Type::D,
             Value::ConstL(_) => Type::L,
+            Value::ConstS(_) => Type::S,
             Value::ConstW(_) => Type::W,
-            Value::VarRef(_, r#type, _) => *r#type,
+            Value::VarRef(_, r#type, _) => r#type.clone(),
+        }
+    }
+}
+
+impl Type {
+    // Sub-word types only exist in memory, they are stored as a byte or half word and loaded
+    // into a word with either a sign or zero extension
+    pub fn store_type(&self) -> Type {
+        match self {
+            Self::SB | Self::UB => Self::B,
+            Self::SH | Self::UH => Self::H,
+            _ => self.clone(),
+        }
+    }
+
+    pub fn value_type(&self) -> Type {
+        match self {
+            Self::B | Self::H | Self::SB | Self::SH | Self::UB | Self::UH => Self::W,
+            _ => self.clone(),
+        }
+    }
+}
+
+// Float literals use the shortest digits that parse back to the same bits. Very large and very
+// small magnitudes switch to an exponent so they stay short. Infinities and NaN have no literal
+// form and are lowered through their bit patterns instead
+pub fn double_lit(d: f64) -> String {
+    assert!(d.is_finite(), "Double literal must be finite");
+
+    match d.abs() {
+        a if a != 0.0 && !(1e-5..1e16).contains(&a) => format!("d_{:e}", d),
+        _ => format!("d_{}", d),
+    }
+}
+
+pub fn single_lit(s: f32) -> String {
+    assert!(s.is_finite(), "Single literal must be finite");
+
+    match s.abs() {
+        a if a != 0.0 && !(1e-5..1e16).contains(&a) => format!("s_{:e}", s),
+        _ => format!("s_{}", s),
+    }
+}
+
+impl Display for Conv {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Cast => write!(f, "cast"),
+            Self::Copy => write!(f, "copy"),
+            Self::Dtosi => write!(f, "dtosi"),
+            Self::Dtoui => write!(f, "dtoui"),
+            Self::Exts => write!(f, "exts"),
+            Self::Extsb => write!(f, "extsb"),
+            Self::Extsh => write!(f, "extsh"),
+            Self::Extsw => write!(f, "extsw"),
+            Self::Extub => write!(f, "extub"),
+            Self::Extuh => write!(f, "extuh"),
+            Self::Extuw => write!(f, "extuw"),
+            Self::Sltof => write!(f, "sltof"),
+            Self::Stosi => write!(f, "stosi"),
+            Self::Stoui => write!(f, "stoui"),
+            Self::Swtof => write!(f, "swtof"),
+            Self::Truncd => write!(f, "truncd"),
+            Self::Ultof => write!(f, "ultof"),
+            Self::Uwtof => write!(f, "uwtof"),
         }
     }
 }
@@ -102,6 +254,9 @@ impl Display for Linkage {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::Export => write!(f, "export"),
+            Self::Section(name, Some(flags)) => write!(f, "section \"{}\" \"{}\"", name, flags),
+            Self::Section(name, None) => write!(f, "section \"{}\"", name),
+            Self::Thread => write!(f, "thread"),
         }
     }
 }
@@ -111,6 +266,18 @@ impl Display for Op {
         match self {
             Self::Eq => write!(f, "eq"),
             Self::Ne => write!(f, "ne"),
+            Self::Sle => write!(f, "sle"),
+            Self::Slt => write!(f, "slt"),
+            Self::Sge => write!(f, "sge"),
+            Self::Sgt => write!(f, "sgt"),
+            Self::Ule => write!(f, "ule"),
+            Self::Ult => write!(f, "ult"),
+            Self::Uge => write!(f, "uge"),
+            Self::Ugt => write!(f, "ugt"),
+            Self::Le => write!(f, "le"),
+            Self::Lt => write!(f, "lt"),
+            Self::Ge => write!(f, "ge"),
+            Self::Gt => write!(f, "gt"),
         }
     }
 }
@@ -120,6 +287,8 @@ impl Display for Scope {
         match self {
             Self::Func => write!(f, "%"),
             Self::Global => write!(f, "$"),
+            // Thread local symbols must be referenced through the thread keyword
+            Self::Thread => write!(f, "thread $"),
         }
     }
 }
@@ -127,9 +296,16 @@ impl Display for Scope {
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
+            Self::Agg(name) => write!(f, ":{}", name),
             Self::B => write!(f, "b"),
             Self::D => write!(f, "d"),
+            Self::H => write!(f, "h"),
             Self::L => write!(f, "l"),
+            Self::S => write!(f, "s"),
+            Self::SB => write!(f, "sb"),
+            Self::SH => write!(f, "sh"),
+            Self::UB => write!(f, "ub"),
+            Self::UH => write!(f, "uh"),
             Self::W => write!(f, "w"),
         }
     }
@@ -1,32 +1,84 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::lower_lang::*;
 
+pub type Aggregate = bool;
 pub type TmpRef = (String, String, Type);
+pub type LoopLbls = (String, String);
+pub type FieldLayout = (String, usize, Type);
+pub type StructLayout = (usize, usize, Vec<FieldLayout>);
 
 pub struct LoweringCtx {
+    allocs: Vec<Stmt>,
+    bound: HashSet<String>,
+    dbg_file: Option<String>,
+    globals: HashMap<String, (Aggregate, Scope)>,
+    loop_lbls: Vec<LoopLbls>,
+    noreturns: HashSet<String>,
     prefix: String,
     pool: BTreeMap<String, String>,
+    struct_layouts: HashMap<String, StructLayout>,
     tmp_refs: Vec<Vec<TmpRef>>,
     uniq: u32,
+    vars: Vec<HashMap<String, String>>,
 }
 
 impl LoweringCtx {
     pub fn new(prefix: &str) -> LoweringCtx {
         LoweringCtx {
+            allocs: Default::default(),
+            bound: Default::default(),
+            dbg_file: Default::default(),
+            globals: Default::default(),
+            loop_lbls: Default::default(),
+            noreturns: Default::default(),
             prefix: prefix.to_string(),
             pool: Default::default(),
+            struct_layouts: Default::default(),
             tmp_refs: Default::default(),
             uniq: 0,
+            vars: Default::default(),
         }
     }
 
+    // Returns true when the file differs from the current one and so needs a dbgfile
+    pub fn set_dbg_file(&mut self, file: &str) -> bool {
+        if self.dbg_file.as_deref() == Some(file) {
+            return false;
+        }
+
+        self.dbg_file = Some(file.to_string());
+        true
+    }
+
+    pub fn dbg_file(&self) -> Option<&str> {
+        self.dbg_file.as_deref()
+    }
+
     pub fn uniq_name(&mut self, prefix: &str) -> String {
         let name = format!("..{}..{}", prefix, self.uniq);
         self.uniq += 1;
         name
     }
 
+    // An alloc outside the start block grows the stack each time it runs, so allocs are kept
+    // for the start block and one inside a loop reuses its slot on every iteration
+    pub fn alloc(&mut self, prefix: &str, size: usize) -> String {
+        let name = self.uniq_name(prefix);
+
+        self.allocs.push(Stmt::VarDecl(
+            name.to_string(),
+            Scope::Func,
+            Expr::Alloc8(size),
+        ));
+
+        name
+    }
+
+    pub fn take_allocs(&mut self) -> Vec<Stmt> {
+        std::mem::take(&mut self.allocs)
+    }
+
     pub fn name_for_str(&mut self, str: &str) -> String {
         let len = self.pool.len();
         self.pool
@@ -36,16 +88,37 @@ impl LoweringCtx {
     }
 
     pub fn decls(&self) -> Vec<Decl> {
+        // Only printable ASCII is emitted as a string, every other byte gets its own field so
+        // the data does not depend on how QBE or the assembler treat escapes
         fn fields(value: &str) -> Vec<DataField> {
-            vec![
-                (Type::B, format!("\"{}\"", str::replace(value, "\n", "\\n"))),
-                (Type::B, "0".to_string()),
-            ]
+            let mut fields = Vec::<DataField>::new();
+            let mut run = String::new();
+
+            for byte in value.bytes() {
+                match byte {
+                    b' '..=b'~' if byte != b'"' && byte != b'\\' => run.push(byte as char),
+                    _ => {
+                        if !run.is_empty() {
+                            fields.push(DataField::Value(Type::B, format!("\"{}\"", run)));
+                            run.clear();
+                        }
+
+                        fields.push(DataField::Value(Type::B, byte.to_string()));
+                    }
+                }
+            }
+
+            if !run.is_empty() {
+                fields.push(DataField::Value(Type::B, format!("\"{}\"", run)));
+            }
+
+            fields.push(DataField::Value(Type::B, "0".to_string()));
+            fields
         }
 
         self.pool
             .iter()
-            .map(|(k, v)| Decl::Data(v.to_string(), fields(k)))
+            .map(|(k, v)| Decl::Data(v.to_string(), vec![], fields(k)))
             .collect()
     }
 
@@ -67,4 +140,92 @@ impl LoweringCtx {
             .pop()
             .expect("Attempting to pop when tmp_refs is empty")
     }
+
+    pub fn push_vars(&mut self) {
+        if self.vars.is_empty() {
+            self.bound.clear();
+        }
+
+        self.vars.push(HashMap::new());
+    }
+
+    pub fn pop_vars(&mut self) {
+        self.vars
+            .pop()
+            .expect("Attempting to pop when vars is empty");
+    }
+
+    // A var keeps its own name the first time it is bound in a func, every later binding gets a
+    // temp of its own so a shadowed binding keeps its value and a sibling of another type does
+    // not give one temp two classes
+    pub fn bind_var(&mut self, name: &str) -> String {
+        let tmp_name = match self.bound.insert(name.to_string()) {
+            true => name.to_string(),
+            false => self.uniq_name(name),
+        };
+
+        self.vars
+            .last_mut()
+            .expect("Attempting to bind a var outside of a scope")
+            .insert(name.to_string(), tmp_name.to_string());
+
+        tmp_name
+    }
+
+    pub fn var(&self, name: &str) -> String {
+        self.vars
+            .iter()
+            .rev()
+            .find_map(|vars| vars.get(name))
+            .map_or_else(|| name.to_string(), String::to_string)
+    }
+
+    pub fn push_loop_lbls(&mut self, loop_lbls: LoopLbls) {
+        self.loop_lbls.push(loop_lbls);
+    }
+
+    pub fn pop_loop_lbls(&mut self) -> LoopLbls {
+        self.loop_lbls
+            .pop()
+            .expect("Attempting to pop when loop_lbls is empty")
+    }
+
+    pub fn loop_lbls(&self) -> &LoopLbls {
+        self.loop_lbls
+            .last()
+            .expect("Attempting to access loop_lbls outside of a loop")
+    }
+
+    pub fn add_global(&mut self, name: &str, aggregate: Aggregate, scope: Scope) {
+        self.globals.insert(name.to_string(), (aggregate, scope));
+    }
+
+    pub fn global(&self, name: &str) -> Option<Aggregate> {
+        self.globals.get(name).map(|g| g.0)
+    }
+
+    pub fn global_scope(&self, name: &str) -> Scope {
+        self.globals
+            .get(name)
+            .map(|g| g.1.clone())
+            .expect("Attempting to access the scope of an undeclared global")
+    }
+
+    pub fn add_noreturn(&mut self, name: &str) {
+        self.noreturns.insert(name.to_string());
+    }
+
+    pub fn noreturn(&self, name: &str) -> bool {
+        self.noreturns.contains(name)
+    }
+
+    pub fn add_struct_layout(&mut self, name: &str, struct_layout: StructLayout) {
+        self.struct_layouts.insert(name.to_string(), struct_layout);
+    }
+
+    pub fn struct_layout(&self, name: &str) -> &StructLayout {
+        self.struct_layouts
+            .get(name)
+            .expect("Attempting to access the layout of an undeclared struct")
+    }
 }